@@ -1,45 +1,922 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
-
-fn multiple_csv_to_hashmap(multiple_csv: Vec<&str>) -> HashMap<&str, Vec<&str>> {
-    let mut map: HashMap<&str, Vec<&str>> = HashMap::new();
-
-    multiple_csv.into_iter().for_each(|csv| {
-        csv.lines()
-            .map(|line| line.split('|').collect::<Vec<&str>>())
-            .for_each(|line_split| {
-                let key = *line_split.first().unwrap();
-                let value = *line_split.get(1).unwrap();
-                map.entry(key).or_insert_with(Vec::new).push(value);
+use rust_tools::error::{self, Error, Location};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[cfg(test)]
+#[path = "../test_util.rs"]
+mod test_util;
+#[cfg(test)]
+use test_util::TempDir;
+
+const USAGE: &str = "\
+Usage: csv-merge [OPTIONS] <LOCALE=FILE>...
+
+Merges `KEY|TEXT` localization files into one `;`-separated table with a
+column per locale, in the order given, headed by the locale codes. Both sides
+are CSV: fields holding a delimiter, a quote or a line break are quoted, and a
+text may contain the input delimiter without quotes. Inputs may be UTF-8 or
+UTF-16 with a byte order mark, keys are trimmed and CRLF line breaks become LF.
+
+    csv-merge en=csv/brisca_en.txt it=csv/brisca_it.txt es=csv/brisca_es.txt
+
+Texts are aligned by key and missing ones are left empty. The first input is
+the primary locale: keys it lacks are reported as orphans, keys another input
+lacks as missing. Keys given twice in a file keep their first text. Keys
+differing only by case or whitespace are reported but kept apart.
+
+Options:
+    -o, --output <FILE>            File the table is written to, `-` for
+                                   stdout [default: -]
+    -d, --delimiter <CHAR>         Delimiter of the table, `tab` for a tab
+                                   [default: ;]
+        --input-delimiter <CHAR>   Delimiter of the inputs [default: |]
+        --order <ORDER>            Row order: `source` for the order of the
+                                   primary input then orphans as first seen,
+                                   `alphabetical` by key or `prefix` for keys
+                                   grouped by the text before their first `_`,
+                                   groups sorted and keys in source order
+                                   [default: source]
+        --strict                   Fail without writing when a key is
+                                   missing, orphan or duplicate
+    -h, --help                     Print this help
+";
+
+/// A localization file and the locale of its texts.
+#[derive(Debug, PartialEq)]
+struct Input {
+    locale: String,
+    path: PathBuf,
+}
+
+/// Order of the rows of the merged table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Order {
+    /// Keys of the primary input in its order, then orphans as first seen.
+    #[default]
+    Source,
+    Alphabetical,
+    /// Keys sharing the text before their first `_`, groups sorted and
+    /// keys in source order.
+    Prefix,
+}
+
+impl std::str::FromStr for Order {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "source" => Ok(Order::Source),
+            "alphabetical" => Ok(Order::Alphabetical),
+            "prefix" => Ok(Order::Prefix),
+            _ => Err(format!("unknown order `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    inputs: Vec<Input>,
+    /// `None` for stdout.
+    output: Option<PathBuf>,
+    delimiter: u8,
+    input_delimiter: u8,
+    order: Order,
+    strict: bool,
+}
+
+enum Command {
+    Merge(Config),
+    Help,
+}
+
+fn parse_input(arg: &str) -> Result<Input, String> {
+    match arg.split_once('=') {
+        Some((locale, path)) if !locale.is_empty() && !path.is_empty() => Ok(Input {
+            locale: locale.to_owned(),
+            path: path.into(),
+        }),
+        _ => Err(format!("invalid input `{arg}`, expected LOCALE=FILE")),
+    }
+}
+
+fn parse_delimiter(arg: &str, value: Option<String>) -> Result<u8, String> {
+    let value = value.ok_or_else(|| format!("{arg} requires a character"))?;
+    match value.as_bytes() {
+        b"tab" => Ok(b'\t'),
+        [byte] if byte.is_ascii() && !matches!(byte, b'"' | b'\n' | b'\r') => Ok(*byte),
+        _ => Err(format!("invalid delimiter `{value}` for {arg}")),
+    }
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut inputs: Vec<Input> = Vec::new();
+    let mut output = None;
+    let mut delimiter = b';';
+    let mut input_delimiter = b'|';
+    let mut order = Order::default();
+    let mut strict = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" | "--output" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a file"))?;
+                output = (path != "-").then(|| path.into());
+            }
+            "-d" | "--delimiter" => delimiter = parse_delimiter(&arg, args.next())?,
+            "--input-delimiter" => input_delimiter = parse_delimiter(&arg, args.next())?,
+            "--order" => {
+                order = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires an order"))?
+                    .parse()?;
+            }
+            "--strict" => strict = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => {
+                let input = parse_input(&arg)?;
+                if inputs.iter().any(|other| other.locale == input.locale) {
+                    return Err(format!("locale `{}` is given twice", input.locale));
+                }
+                inputs.push(input);
+            }
+        }
+    }
+
+    if inputs.is_empty() {
+        return Err("no input given".to_owned());
+    }
+
+    Ok(Command::Merge(Config {
+        inputs,
+        output,
+        delimiter,
+        input_delimiter,
+        order,
+        strict,
+    }))
+}
+
+/// A key that does not line up across the inputs.
+#[derive(Debug, PartialEq)]
+enum Issue {
+    /// In the primary input but not in the one of `locale`.
+    Missing { locale: String, key: String },
+    /// Not in the primary input.
+    Orphan { locale: String, key: String },
+    /// Given again at `at`, after its first text.
+    Duplicate { at: Location, key: String },
+    /// Equal to the earlier `first` but for case or whitespace.
+    Similar { key: String, first: String },
+}
+
+impl Issue {
+    /// Whether the texts of the key may end up in the wrong row, which
+    /// `--strict` does not allow. Similar keys can be on purpose.
+    fn is_mismatch(&self) -> bool {
+        !matches!(self, Issue::Similar { .. })
+    }
+
+    /// The key the issue is about.
+    fn key(&self) -> &str {
+        match self {
+            Issue::Missing { key, .. }
+            | Issue::Orphan { key, .. }
+            | Issue::Duplicate { key, .. }
+            | Issue::Similar { key, .. } => key,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Missing { locale, key } => write!(f, "{locale}: missing key `{key}`"),
+            Issue::Orphan { locale, key } => {
+                write!(f, "{locale}: key `{key}` is not in the primary locale")
+            }
+            Issue::Duplicate { at, key } => {
+                write!(f, "{at}: duplicate key `{key}`, keeping the first text")
+            }
+            Issue::Similar { key, first } => write!(
+                f,
+                "key `{key}` differs from `{first}` only by case or whitespace"
+            ),
+        }
+    }
+}
+
+/// A text of an input file.
+#[derive(Debug, Clone, PartialEq)]
+struct Text {
+    at: Location,
+    key: String,
+    value: String,
+}
+
+/// The `KEY|TEXT` records of a file, `|` being `delimiter`.
+///
+/// Unquoted delimiters after the key belong to the text. Keys are trimmed and
+/// line breaks in texts become LF. A quote opening a field must be closed
+/// right before a delimiter or the end of a line.
+fn parse_texts(file: &str, text: &str, delimiter: u8) -> error::Result<Vec<Text>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    // Positions of the csv crate are where it started skipping line breaks
+    // and blank lines, so lines are counted from the first byte of the record
+    let newlines: Vec<usize> = text.match_indices('\n').map(|(at, _)| at).collect();
+    let location = |position: Option<&csv::Position>| {
+        let (byte, record) = position.map_or((0, 0), |pos| (pos.byte() as usize, pos.record()));
+        let skipped = text.as_bytes()[byte.min(text.len())..]
+            .iter()
+            .take_while(|byte| matches!(byte, b'\r' | b'\n'))
+            .count();
+        let line = newlines.partition_point(|newline| *newline < byte + skipped) + 1;
+        Location {
+            file: file.to_owned(),
+            line: line as u64,
+            record,
+        }
+    };
+
+    let separator = char::from(delimiter).to_string();
+
+    let mut texts = Vec::new();
+    let mut record = csv::StringRecord::new();
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => {
+                return Err(Error::MalformedRow {
+                    at: location(err.position()),
+                    column: None,
+                    message: err.to_string(),
+                })
+            }
+        }
+        let at = location(record.position());
+        let start = record.position().map_or(0, |pos| pos.byte() as usize);
+        let raw = &text[start..reader.position().byte() as usize];
+        let fields: Vec<&str> = record.iter().collect();
+        let message = match (quoting_error(raw, delimiter), &fields[..]) {
+            (Some(message), _) => message,
+            (None, [] | [_]) => format!("expected KEY{separator}TEXT"),
+            (None, [key, ..]) if key.trim().is_empty() => "empty key".to_owned(),
+            (None, [key, value @ ..]) => {
+                texts.push(Text {
+                    at,
+                    key: key.trim().to_owned(),
+                    value: value.join(&separator).replace("\r\n", "\n"),
+                });
+                continue;
+            }
+        };
+        return Err(Error::MalformedRow {
+            at,
+            column: None,
+            message,
+        });
+    }
+    Ok(texts)
+}
+
+/// What is wrong with the quoting of the `raw` text of a record, which the
+/// csv crate accepts however broken: text after a closing quote is appended
+/// to the field without the quotes, and a quote never closed takes in every
+/// line up to the end of the file.
+fn quoting_error(raw: &str, delimiter: u8) -> Option<String> {
+    let bytes = raw.as_bytes();
+    let mut at = bytes
+        .iter()
+        .take_while(|byte| matches!(byte, b'\r' | b'\n'))
+        .count();
+    while at < bytes.len() {
+        if bytes[at] != b'"' {
+            match bytes[at..]
+                .iter()
+                .position(|&byte| matches!(byte, b'\r' | b'\n') || byte == delimiter)
+            {
+                Some(len) if bytes[at + len] == delimiter => at += len + 1,
+                _ => return None,
+            }
+            continue;
+        }
+        // `""` is a quote inside the field
+        let mut end = at + 1;
+        while end < bytes.len() && (bytes[end] != b'"' || bytes.get(end + 1) == Some(&b'"')) {
+            end += if bytes[end] == b'"' { 2 } else { 1 };
+        }
+        let quoted = &raw[at + 1..end];
+        if let Some(key) = quoted.lines().skip(1).find_map(|line| {
+            let (key, _) = line.split_once(char::from(delimiter))?;
+            let is_key = !key.is_empty() && !key.contains(|c: char| c.is_whitespace() || c == '"');
+            is_key.then_some(key)
+        }) {
+            return Some(format!("quoted text runs into the line of key `{key}`"));
+        }
+        if end == bytes.len() {
+            return Some("quoted text is never closed".to_owned());
+        }
+        match bytes.get(end + 1) {
+            None | Some(b'\r' | b'\n') => return None,
+            Some(&byte) if byte == delimiter => at = end + 2,
+            Some(_) => return Some("text after the closing quote of a field".to_owned()),
+        }
+    }
+    None
+}
+
+/// Texts by key with one cell per input, `None` for missing translations.
+#[derive(Debug, Default)]
+struct Merged<'a> {
+    rows: HashMap<&'a str, Vec<Option<&'a str>>>,
+    /// Keys of `rows` as first seen, primary input first.
+    keys: Vec<&'a str>,
+    issues: Vec<Issue>,
+}
+
+impl<'a> Merged<'a> {
+    fn ordered_keys(&self, order: Order) -> Vec<&'a str> {
+        let mut keys = self.keys.clone();
+        match order {
+            Order::Source => {}
+            Order::Alphabetical => keys.sort_unstable(),
+            // Stable, so groups keep the source order
+            Order::Prefix => keys.sort_by_key(|key| key.split('_').next().unwrap_or_default()),
+        }
+        keys
+    }
+}
+
+/// Aligns the texts of every input by key, the first input being the
+/// primary one.
+fn merge_texts<'a>(locales: &[&str], inputs: &'a [Vec<Text>]) -> Merged<'a> {
+    let mut merged = Merged::default();
+    for (column, texts) in inputs.iter().enumerate() {
+        for Text { at, key, value } in texts {
+            let cells = merged.rows.entry(key.as_str()).or_insert_with(|| {
+                merged.keys.push(key);
+                vec![None; locales.len()]
             });
-    });
+            if cells[column].is_some() {
+                merged.issues.push(Issue::Duplicate {
+                    at: at.clone(),
+                    key: key.to_string(),
+                });
+                continue;
+            }
+            cells[column] = Some(value.as_str());
+            if column > 0 && cells[0].is_none() {
+                merged.issues.push(Issue::Orphan {
+                    locale: locales[column].to_owned(),
+                    key: key.to_string(),
+                });
+            }
+        }
+    }
+
+    // Keys of the primary input, each once
+    let mut seen = HashSet::new();
+    let primary_keys: Vec<&str> = inputs
+        .first()
+        .into_iter()
+        .flatten()
+        .map(|text| text.key.as_str())
+        .filter(|key| seen.insert(*key))
+        .collect();
+    for (column, locale) in locales.iter().enumerate().skip(1) {
+        for key in &primary_keys {
+            if merged.rows[key][column].is_none() {
+                merged.issues.push(Issue::Missing {
+                    locale: locale.to_string(),
+                    key: key.to_string(),
+                });
+            }
+        }
+    }
 
-    map
+    let mut folded: HashMap<String, &str> = HashMap::new();
+    for key in &merged.keys {
+        let fold = key
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+        match folded.entry(fold) {
+            Entry::Occupied(first) => merged.issues.push(Issue::Similar {
+                key: key.to_string(),
+                first: first.get().to_string(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(key);
+            }
+        }
+    }
+    merged
 }
 
-fn change_parameters_format(input: &mut String) {
-    // TODO: Replace with Regex for consistency
-    *input = input.replace("[%", "{");
-    *input = input.replace(']', "}");
+/// The text of `bytes`, decoded as UTF-16 after its byte order mark or as
+/// UTF-8 otherwise, without the byte order mark.
+fn decode(path: &Path, bytes: Vec<u8>) -> error::Result<String> {
+    let invalid = |message: &str| Error::Io {
+        path: path.to_owned(),
+        source: io::Error::new(io::ErrorKind::InvalidData, message),
+    };
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        if !bytes.len().is_multiple_of(2) {
+            return Err(invalid("odd number of bytes in UTF-16 text"));
+        }
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).map_err(|_| invalid("stream did not contain valid UTF-16"))
+    };
+
+    match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec())
+            .map_err(|_| invalid("stream did not contain valid UTF-8")),
+        _ => String::from_utf8(bytes).map_err(|_| invalid("stream did not contain valid UTF-8")),
+    }
 }
 
-fn main() {
-    let mut input: Vec<&str> = Vec::new();
-    input.push(include_str!("../../csv/brisca_en.txt"));
-    input.push(include_str!("../../csv/brisca_it.txt"));
-    input.push(include_str!("../../csv/brisca_es.txt"));
+/// The merged table, one column per input, and the keys that do not line up.
+fn merge(config: &Config) -> error::Result<(String, Vec<Issue>)> {
+    let mut files: Vec<String> = Vec::new();
+    for input in &config.inputs {
+        let bytes = fs::read(&input.path).map_err(|source| Error::Io {
+            path: input.path.clone(),
+            source,
+        })?;
+        files.push(decode(&input.path, bytes)?);
+    }
+    let mut inputs = Vec::new();
+    for (input, text) in config.inputs.iter().zip(&files) {
+        let file = input.path.display().to_string();
+        inputs.push(parse_texts(&file, text, config.input_delimiter)?);
+    }
+
+    let locales: Vec<&str> = config
+        .inputs
+        .iter()
+        .map(|input| input.locale.as_str())
+        .collect();
+    let merged = merge_texts(&locales, &inputs);
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(config.delimiter)
+        .from_writer(Vec::new());
+    // Writing to memory cannot fail
+    let mut header = vec!["Key", "Type", "Desc"];
+    header.extend(&locales);
+    writer.write_record(&header).unwrap();
+    for key in merged.ordered_keys(config.order) {
+        let mut record = vec![key, "Text", ""];
+        record.extend(merged.rows[key].iter().map(|cell| cell.unwrap_or_default()));
+        writer.write_record(&record).unwrap();
+    }
+    let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+    Ok((output, merged.issues))
+}
+
+fn main() -> ExitCode {
+    let config = match parse_args(env::args().skip(1)) {
+        Ok(Command::Merge(config)) => config,
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let (merged, issues) = match merge(&config) {
+        Ok(merged) => merged,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for issue in &issues {
+        eprintln!("warning: {issue}");
+    }
+    let mismatched: HashSet<&str> = issues
+        .iter()
+        .filter(|issue| issue.is_mismatch())
+        .map(Issue::key)
+        .collect();
+    if config.strict && !mismatched.is_empty() {
+        let count = match mismatched.len() {
+            1 => "1 key does".to_owned(),
+            n => format!("{n} keys do"),
+        };
+        eprintln!("error: {count} not line up, nothing written because of --strict");
+        return ExitCode::FAILURE;
+    }
+
+    match write_output(&config, &merged) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn write_output(config: &Config, merged: &str) -> error::Result<()> {
+    match &config.output {
+        Some(path) => fs::write(path, merged).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        }),
+        None => {
+            let mut stdout = io::stdout().lock();
+            match stdout
+                .write_all(merged.as_bytes())
+                .and_then(|()| stdout.flush())
+            {
+                // The reader having enough, as with `| head`, is not an error
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(Error::Io {
+                    path: PathBuf::from("<stdout>"),
+                    source: err,
+                }),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let args = [
+        "en=csv/brisca_en.txt",
+        "-o",
+        "merged.csv",
+        "--strict",
+        "pt-BR=pt.txt",
+        "--order",
+        "prefix",
+        "-d",
+        "tab",
+        "--input-delimiter",
+        "=",
+    ];
+
+    let Ok(Command::Merge(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(
+        config.inputs,
+        vec![
+            Input {
+                locale: "en".to_owned(),
+                path: "csv/brisca_en.txt".into(),
+            },
+            Input {
+                locale: "pt-BR".to_owned(),
+                path: "pt.txt".into(),
+            },
+        ]
+    );
+    assert_eq!(config.output, Some(PathBuf::from("merged.csv")));
+    assert_eq!(config.delimiter, b'\t');
+    assert_eq!(config.input_delimiter, b'=');
+    assert_eq!(config.order, Order::Prefix);
+    assert!(config.strict);
+
+    let Ok(Command::Merge(config)) = parse_args(["-o", "-", "en=en.txt"].map(String::from)) else {
+        panic!("arguments should parse");
+    };
+    assert_eq!(config.output, None);
+    assert_eq!(config.delimiter, b';');
+    assert_eq!(config.input_delimiter, b'|');
+    assert_eq!(config.order, Order::Source);
+    assert!(!config.strict);
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert!(parse_args(Vec::new()).is_err());
+    assert!(parse_args(["-o", "merged.csv"].map(String::from)).is_err());
+    assert!(parse_args(["brisca_en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["=en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["en="].map(String::from)).is_err());
+    assert!(parse_args(["en=a.txt", "en=b.txt"].map(String::from)).is_err());
+    assert!(parse_args(["--verbose", "en=en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["--order", "random", "en=en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["-d", ";;", "en=en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["-d", "\"", "en=en.txt"].map(String::from)).is_err());
+    assert!(parse_args(["--input-delimiter", "é", "en=en.txt"].map(String::from)).is_err());
+}
+
+#[test]
+fn test_merge_texts() {
+    let en = "a|A\nb|B\nc|C\n";
+    let it = "b|B it\na|A it\nb|B again\nd|D it\n";
+    let es = "\nc|C es\nd|D es\n";
+    let inputs: Vec<_> = [("en.txt", en), ("it.txt", it), ("es.txt", es)]
+        .into_iter()
+        .map(|(file, text)| parse_texts(file, text, b'|').unwrap())
+        .collect();
+
+    let merged = merge_texts(&["en", "it", "es"], &inputs);
+
+    assert_eq!(merged.rows.len(), 4);
+    assert_eq!(merged.rows["a"], [Some("A"), Some("A it"), None]);
+    assert_eq!(merged.rows["b"], [Some("B"), Some("B it"), None]);
+    assert_eq!(merged.rows["c"], [Some("C"), None, Some("C es")]);
+    assert_eq!(merged.rows["d"], [None, Some("D it"), Some("D es")]);
+    let issues: Vec<String> = merged.issues.iter().map(Issue::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "it.txt:3: duplicate key `b`, keeping the first text",
+            "it: key `d` is not in the primary locale",
+            "es: key `d` is not in the primary locale",
+            "it: missing key `c`",
+            "es: missing key `a`",
+            "es: missing key `b`",
+        ]
+    );
+}
+
+#[test]
+fn test_order() {
+    let en = "MENU_PLAY|Play\nHELP_TITLE|Help\nMENU_EXIT|Exit\nBACK|Back\n";
+    let it = "HELP_TEXT|Aiuto\nMENU_PLAY|Gioca\n";
+    let inputs: Vec<_> = [("en.txt", en), ("it.txt", it)]
+        .into_iter()
+        .map(|(file, text)| parse_texts(file, text, b'|').unwrap())
+        .collect();
+
+    let merged = merge_texts(&["en", "it"], &inputs);
+
+    assert_eq!(
+        merged.ordered_keys(Order::Source),
+        ["MENU_PLAY", "HELP_TITLE", "MENU_EXIT", "BACK", "HELP_TEXT"]
+    );
+    assert_eq!(
+        merged.ordered_keys(Order::Alphabetical),
+        ["BACK", "HELP_TEXT", "HELP_TITLE", "MENU_EXIT", "MENU_PLAY"]
+    );
+    assert_eq!(
+        merged.ordered_keys(Order::Prefix),
+        ["BACK", "HELP_TITLE", "HELP_TEXT", "MENU_PLAY", "MENU_EXIT"]
+    );
+}
+
+#[test]
+fn test_parse_texts_errors() {
+    let Err(err) = parse_texts("en.txt", "a|A\nb\n", b'|') else {
+        panic!("a line without text should fail");
+    };
+
+    assert_eq!(
+        err.to_string(),
+        "en.txt:2: malformed row: expected KEY|TEXT"
+    );
+    let Err(err) = parse_texts("en.txt", "a;A\n;B\n", b';') else {
+        panic!("an empty key should fail");
+    };
+    assert_eq!(err.to_string(), "en.txt:2: malformed row: empty key");
+}
+
+#[test]
+fn test_parse_texts_quoting() {
+    let it = include_str!("../../csv/brisca_it.txt");
+
+    let texts = parse_texts("brisca_it.txt", it, b'|').unwrap();
+
+    let premium = texts
+        .iter()
+        .find(|text| text.key == "MSG_BLOCK_PREMIUM")
+        .unwrap();
+    assert_eq!(
+        premium.value,
+        "VINCI [%num] PARTITE A \"SFIDA [%name]\" PER GIOCARE!"
+    );
+    assert_eq!(premium.at.line, 399);
+
+    let texts = parse_texts(
+        "nasty.txt",
+        "PIPE|Carte | Punti|Totale\r\n\r\nQUOTED|\"Riga uno\r\nRiga \"\"due\"\"\"\r\nEND|Fine\r\n",
+        b'|',
+    )
+    .unwrap();
+    let values: Vec<&str> = texts.iter().map(|text| text.value.as_str()).collect();
+    assert_eq!(
+        values,
+        ["Carte | Punti|Totale", "Riga uno\nRiga \"due\"", "Fine"]
+    );
+    let lines: Vec<u64> = texts.iter().map(|text| text.at.line).collect();
+    assert_eq!(lines, [1, 3, 5]);
+
+    // The csv crate would read these as `Hi she said` and as a text of A
+    // taking in the following keys
+    let Err(err) = parse_texts("quotes.txt", "A|\"Hi\" she said\n", b'|') else {
+        panic!("text after a closing quote should fail");
+    };
+    assert_eq!(
+        err.to_string(),
+        "quotes.txt:1: malformed row: text after the closing quote of a field"
+    );
+    let Err(err) = parse_texts("quotes.txt", "A|\"open quote\nB|two\nC|three", b'|') else {
+        panic!("a quote running into the next keys should fail");
+    };
+    assert_eq!(
+        err.to_string(),
+        "quotes.txt:1: malformed row: quoted text runs into the line of key `B`"
+    );
+    let Err(err) = parse_texts("quotes.txt", "A|one\nB|\"open\nquote\n", b'|') else {
+        panic!("a quote never closed should fail");
+    };
+    assert_eq!(
+        err.to_string(),
+        "quotes.txt:2: malformed row: quoted text is never closed"
+    );
+}
+
+#[test]
+fn test_decode() {
+    let path = Path::new("brisca_it.txt");
+    let text = " LOADING_INTRO|CARICAMENTO…\r\n";
+    let utf16 = |bom: [u8; 2], to_bytes: fn(u16) -> [u8; 2]| {
+        let mut bytes = bom.to_vec();
+        bytes.extend(text.encode_utf16().flat_map(to_bytes));
+        bytes
+    };
+
+    assert_eq!(decode(path, text.into()).unwrap(), text);
+    let bom = [b"\xEF\xBB\xBF", text.as_bytes()].concat();
+    assert_eq!(decode(path, bom).unwrap(), text);
+    assert_eq!(
+        decode(path, utf16([0xFF, 0xFE], u16::to_le_bytes)).unwrap(),
+        text
+    );
+    assert_eq!(
+        decode(path, utf16([0xFE, 0xFF], u16::to_be_bytes)).unwrap(),
+        text
+    );
+
+    let mut odd = utf16([0xFF, 0xFE], u16::to_le_bytes);
+    odd.pop();
+    assert_eq!(
+        decode(path, odd).unwrap_err().to_string(),
+        "brisca_it.txt: odd number of bytes in UTF-16 text"
+    );
+    assert!(matches!(
+        decode(path, vec![0xFF, 0xFE, 0x00, 0xD8]),
+        Err(Error::Io { .. })
+    ));
+    assert!(matches!(
+        decode(path, b"KEY|\xE8\n".to_vec()),
+        Err(Error::Io { .. })
+    ));
+}
+
+#[test]
+fn test_normalization() {
+    let en = "LOADING_INTRO|LOADING\r\nMENU PLAY|Play\r\n";
+    let it = " LOADING_INTRO |CARICAMENTO\r\nmenu_play|Gioca\r\nMENUPLAY|Gioca\r\n";
+    let inputs: Vec<_> = [("en.txt", en), ("it.txt", it)]
+        .into_iter()
+        .map(|(file, text)| parse_texts(file, text, b'|').unwrap())
+        .collect();
+
+    let merged = merge_texts(&["en", "it"], &inputs);
+
+    assert_eq!(
+        merged.rows["LOADING_INTRO"],
+        [Some("LOADING"), Some("CARICAMENTO")]
+    );
+    let issues: Vec<String> = merged.issues.iter().map(Issue::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "it: key `menu_play` is not in the primary locale",
+            "it: key `MENUPLAY` is not in the primary locale",
+            "it: missing key `MENU PLAY`",
+            "key `MENUPLAY` differs from `MENU PLAY` only by case or whitespace",
+        ]
+    );
+    assert!(!merged.issues[3].is_mismatch());
+}
+
+#[test]
+fn test_merge_quoting() {
+    let temp_dir = TempDir::new("test-quoting");
+    let dir = temp_dir.path();
+    let it = include_str!("../../csv/brisca_it.txt");
+    let premium = it
+        .lines()
+        .find(|line| line.starts_with("MSG_BLOCK_PREMIUM|"))
+        .unwrap();
+    fs::write(
+        dir.join("en.txt"),
+        "MSG_BLOCK_PREMIUM|WIN [%num] \"[%name]\" GAMES; THEN PLAY|NOW\nEMPTY|\n",
+    )
+    .unwrap();
+    fs::write(dir.join("it.txt"), format!("{premium}\nEMPTY|\"\"\n")).unwrap();
+    let config = Config {
+        inputs: ["en", "it"]
+            .map(|locale| Input {
+                locale: locale.to_owned(),
+                path: dir.join(format!("{locale}.txt")),
+            })
+            .into(),
+        output: None,
+        delimiter: b';',
+        input_delimiter: b'|',
+        order: Order::Source,
+        strict: false,
+    };
+
+    let (merged, issues) = merge(&config).unwrap();
+
+    assert!(issues.is_empty());
+    assert_eq!(
+        merged,
+        "Key;Type;Desc;en;it\n\
+         MSG_BLOCK_PREMIUM;Text;;\"WIN [%num] \"\"[%name]\"\" GAMES; THEN PLAY|NOW\";\
+         \"VINCI [%num] PARTITE A \"\"SFIDA [%name]\"\" PER GIOCARE!\"\n\
+         EMPTY;Text;;;\n"
+    );
+    // Reading the table back gives the texts unchanged
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(merged.as_bytes());
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[3], "WIN [%num] \"[%name]\" GAMES; THEN PLAY|NOW");
+    assert_eq!(&record[4], &premium["MSG_BLOCK_PREMIUM|".len()..]);
+}
 
-    let mut output = String::new();
-    output.push_str("Key;Type;Desc;English;Italian;Spanish\n");
+#[test]
+fn test_merge() {
+    let config = Config {
+        inputs: ["en", "es"]
+            .map(|locale| Input {
+                locale: locale.to_owned(),
+                path: format!("csv/brisca_{locale}.txt").into(),
+            })
+            .into(),
+        output: None,
+        delimiter: b';',
+        input_delimiter: b'|',
+        order: Order::Source,
+        strict: false,
+    };
 
-    multiple_csv_to_hashmap(input).into_iter().for_each(|key| {
-        output.push_str(&format!("{};Text;;{}\n", key.0, key.1.join(";")));
-    });
+    let (merged, issues) = merge(&config).unwrap();
 
-    // Changing terms format from [%something] to {something}
-    // change_parameters_format(&mut output);
+    assert!(merged.starts_with(
+        "Key;Type;Desc;en;es\n\
+         LOADING_INTRO;Text;;LOADING, PLEASE WAIT...;CARGANDO, POR FAVOR ESPERE...\n\
+         HAS_NICKNAME_PASSWORD;Text;;"
+    ));
+    assert_eq!(merge(&config).unwrap().0, merged);
+    assert_eq!(merged.lines().count(), 458);
+    assert!(merged
+        .contains("\nLOADING_INTRO;Text;;LOADING, PLEASE WAIT...;CARGANDO, POR FAVOR ESPERE...\n"));
+    let issues: Vec<String> = issues.iter().map(Issue::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "key `BEGINNER` differs from `Beginner` only by case or whitespace",
+            "key `INTERMEDIATE` differs from `Intermediate` only by case or whitespace",
+            "key `EXPERT` differs from `Expert` only by case or whitespace",
+        ]
+    );
 
-    let mut file = File::create("brisca_merged.csv").unwrap();
-    file.write_all(output.as_bytes()).unwrap();
+    let missing = Config {
+        inputs: vec![Input {
+            locale: "en".to_owned(),
+            path: "csv/brisca_xx.txt".into(),
+        }],
+        output: None,
+        delimiter: b';',
+        input_delimiter: b'|',
+        order: Order::Source,
+        strict: false,
+    };
+    assert!(matches!(merge(&missing), Err(Error::Io { .. })));
 }
@@ -1,384 +1,1978 @@
-use csv::Reader;
 use itertools::Itertools;
-use rust_tools::get_type_efficacy;
+use rust_tools::coverage::Coverage;
+use rust_tools::csv_source::CsvSource;
+use rust_tools::damage::{self, Combatant};
+use rust_tools::emit::{self, Format, Lua};
+use rust_tools::error::{self, Diagnostics, Error, OnBadRow};
+use rust_tools::generation::Generation;
+use rust_tools::language::{self, Language};
+use rust_tools::pokedex::{DamageClass, LearnMethod, MoveFilter, Pokedex, Pokemon};
+use rust_tools::tables::{self, FormLayout};
+use rust_tools::team::{Member, TeamReport, MAX_TEAM_SIZE};
+use rust_tools::types::Type;
 use serde::Deserialize;
-use std::collections::BTreeMap;
-use std::fs::File;
-use std::io::Write;
-use std::result::Result;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[cfg(test)]
+#[path = "../test_util.rs"]
+mod test_util;
+#[cfg(test)]
+use test_util::TempDir;
+
+const USAGE: &str = "\
+Usage: csv-to-lua-table [OPTIONS] <TABLE>...
+       csv-to-lua-table coverage [OPTIONS] [TYPE]...
+       csv-to-lua-table team-report [OPTIONS] <POKEMON>...
+       csv-to-lua-table damage [OPTIONS] <ATTACKER> <MOVE_ID> <DEFENDER>
+       csv-to-lua-table manifest <FILE>
+
+Tables:
+    pokemon-names        pokemon.csv -> pokemon_names
+    pokemon-attributes   pokemon.csv -> pokemon_attributes
+    pokemon-stats        pokemon_stats.csv -> pokemon_stats
+    pokemon-abilities    pokemon_abilities.csv -> pokemon_abilities
+    pokemon-types        pokemon_types.csv -> pokemon_types
+    pokemon-weaknesses   pokemon_types.csv -> pokemon_weaknesses
+    pokemon-defenses     pokemon_types.csv -> pokemon_defenses
+    type-efficacy        type_efficacy.csv -> type_efficacy
+    moves                moves.csv -> moves
+    move-names           move_names.csv -> move_names
+    evolutions           pokemon_species.csv, pokemon_evolution.csv -> evolutions
+    learnsets            pokemon_moves.csv -> learnsets
+    all                  every table above
+
+Options:
+    -i, --input-dir <DIR>    Directory containing the PokeAPI CSV files [default: csv]
+    -o, --output-dir <DIR>   Directory the tables are written to [default: output]
+    -f, --format <FORMAT>    Output format: lua, json, c or rust, repeatable [default: lua]
+        --combined           Write every Lua table into a single pokedex.lua module
+    -g, --generation <N>     Only export what exists in generation N, with its type
+                             chart; pokemon_species.csv and pokemon_types_past.csv
+                             are used when present
+        --language <CHAIN>   Export pokemon-names and move-names in the first
+                             language of CHAIN having a translation, such as
+                             `it,en`, falling back to the identifier; languages
+                             are ISO codes or PokeAPI ids. Repeat to write one
+                             table per chain, e.g. pokemon_names_it. Reads
+                             pokemon_species_names.csv and moves.csv
+        --forms <LAYOUT>     Layout of the tables keyed by Pokemon: `species` for
+                             default forms keyed by species id, `all` for every
+                             form keyed by Pokemon id or `nested` for the forms
+                             of each species under its id [default: all]
+        --skip-bad-rows      Warn about malformed rows instead of failing
+    -h, --help               Print this help
+
+Move filters, for the moves table:
+        --damage-class <CLASS>     physical, special or status; only status
+                                   includes moves without a base power
+        --move-generation <RANGE>  Generations such as `4`, `..4` or `3..5`
+        --min-accuracy <N>         Moves that never miss always pass
+        --priority <RANGE>         Priorities such as `1..` or `-7..-1`
+
+Learnset filters, for the learnsets table:
+        --version-group <ID>       PokeAPI version group [default: the latest one]
+        --learn-method <METHOD>    level-up, egg, tutor, machine or another
+                                   PokeAPI method, repeatable [default: all]
+
+Manifest:
+    Writes the tables listed in a TOML manifest, one `[[export]]` entry per
+    output file; see csv-to-lua-table.toml. Paths are relative to the manifest.
+
+    input_dir, output_dir    As the options above [default: csv, output]
+    skip_bad_rows            true to warn about malformed rows
+    [[export]]
+    table                    One of the tables above, `all` excepted
+    target                   Output file, relative to output_dir
+    source                   Input file replacing the default one, for tables
+                             reading a single file
+    format                   lua, json, c or rust [default: from the target
+                             extension .lua, .json, .h or .rs]
+    language, generation, forms, damage_class, move_generation, min_accuracy,
+    priority, version_group  As the options above, e.g. `language = \"it,en\"`,
+                             `generation = 3` or `priority = \"1..\"`
+    learn_methods            List of learn methods, e.g. [\"level-up\"]
+
+Coverage:
+    Prints the best multiplier the attacking types reach against every single
+    and dual type combination, then the combinations resisting all of them.
+    TYPE is an identifier or id such as `fire` or `10`.
+
+    -m, --move <ID>          Add the type of move <ID> from moves.csv, repeatable
+
+Team report:
+    Prints the multiplier of every attacking type against each of up to six
+    Pokemon, with the number of weak, resisting and immune members. Types
+    hitting two members or more super effectively are flagged.
+    POKEMON is an identifier or id such as `charizard` or `6`.
+
+    -f, --format <FORMAT>    Output format: table, csv or json [default: table]
+
+Damage:
+    Prints the damage range of one hit, from the lowest to the highest random
+    roll, with perfect IVs, no EVs and neutral natures. Reads pokemon.csv,
+    pokemon_types.csv, pokemon_stats.csv and moves.csv.
+
+    -l, --level <N>          Level of both Pokemon [default: 50]
+        --defender-level <N> Level of the defender [default: --level]
+        --critical           Compute a critical hit
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    PokemonNames,
+    PokemonAttributes,
+    PokemonStats,
+    PokemonAbilities,
+    PokemonTypes,
+    PokemonWeaknesses,
+    PokemonDefenses,
+    TypeEfficacy,
+    Moves,
+    MoveNames,
+    Evolutions,
+    Learnsets,
+}
 
-const EMPTY_TYPE: u64 = 0;
-const EFFECTIVE: u64 = 100;
-const SUPER_EFFECTIVE: u64 = 200;
+impl Table {
+    const ALL: [Table; 12] = [
+        Table::PokemonNames,
+        Table::PokemonAttributes,
+        Table::PokemonStats,
+        Table::PokemonAbilities,
+        Table::PokemonTypes,
+        Table::PokemonWeaknesses,
+        Table::PokemonDefenses,
+        Table::TypeEfficacy,
+        Table::Moves,
+        Table::MoveNames,
+        Table::Evolutions,
+        Table::Learnsets,
+    ];
+
+    fn from_command(command: &str) -> Option<Table> {
+        Table::ALL
+            .into_iter()
+            .find(|table| table.command() == command)
+    }
 
-#[derive(Debug, Deserialize)]
-struct Pokemon {
-    id: u64,
-    #[serde(rename = "identifier")]
-    name: String,
+    fn command(self) -> &'static str {
+        match self {
+            Table::PokemonNames => "pokemon-names",
+            Table::PokemonAttributes => "pokemon-attributes",
+            Table::PokemonStats => "pokemon-stats",
+            Table::PokemonAbilities => "pokemon-abilities",
+            Table::PokemonTypes => "pokemon-types",
+            Table::PokemonWeaknesses => "pokemon-weaknesses",
+            Table::PokemonDefenses => "pokemon-defenses",
+            Table::TypeEfficacy => "type-efficacy",
+            Table::Moves => "moves",
+            Table::MoveNames => "move-names",
+            Table::Evolutions => "evolutions",
+            Table::Learnsets => "learnsets",
+        }
+    }
+
+    fn input_files(self) -> &'static [&'static str] {
+        match self {
+            Table::PokemonNames | Table::PokemonAttributes => &["pokemon.csv"],
+            Table::PokemonStats => &["pokemon_stats.csv"],
+            Table::PokemonAbilities => &["pokemon_abilities.csv"],
+            Table::PokemonTypes | Table::PokemonWeaknesses | Table::PokemonDefenses => {
+                &["pokemon_types.csv"]
+            }
+            Table::TypeEfficacy => &["type_efficacy.csv"],
+            Table::Moves => &["moves.csv"],
+            Table::MoveNames => &["move_names.csv"],
+            Table::Evolutions => &["pokemon_species.csv", "pokemon_evolution.csv"],
+            Table::Learnsets => &["pokemon_moves.csv"],
+        }
+    }
+
+    /// Extra input telling which rows belong to a generation.
+    fn generation_input_file(self) -> Option<&'static str> {
+        match self {
+            Table::PokemonTypes
+            | Table::PokemonWeaknesses
+            | Table::PokemonDefenses
+            | Table::PokemonStats
+            | Table::PokemonAbilities => Some("pokemon.csv"),
+            Table::MoveNames | Table::Learnsets => Some("moves.csv"),
+            Table::PokemonNames
+            | Table::PokemonAttributes
+            | Table::TypeEfficacy
+            | Table::Moves
+            | Table::Evolutions => None,
+        }
+    }
+
+    fn is_keyed_by_pokemon(self) -> bool {
+        matches!(
+            self,
+            Table::PokemonNames
+                | Table::PokemonAttributes
+                | Table::PokemonStats
+                | Table::PokemonAbilities
+                | Table::PokemonTypes
+                | Table::PokemonWeaknesses
+                | Table::PokemonDefenses
+                | Table::Learnsets
+        )
+    }
+
+    /// Extra input providing translations or the fallback identifiers.
+    fn language_input_file(self) -> Option<&'static str> {
+        match self {
+            Table::PokemonNames => Some("pokemon_species_names.csv"),
+            Table::MoveNames => Some("moves.csv"),
+            _ => None,
+        }
+    }
+
+    /// The table, or one table per language chain for the names tables.
+    fn build(self, dex: &Pokedex, config: &Config) -> Vec<emit::Table> {
+        let languages = &config.languages;
+        match self {
+            Table::PokemonNames if !languages.is_empty() => languages
+                .iter()
+                .map(|chain| tables::localized_pokemon_names(dex, chain))
+                .collect(),
+            Table::MoveNames if !languages.is_empty() => languages
+                .iter()
+                .map(|chain| tables::localized_move_names(dex, chain))
+                .collect(),
+            Table::PokemonNames => vec![tables::pokemon_names(dex)],
+            Table::PokemonAttributes => vec![tables::pokemon_attributes(dex)],
+            Table::PokemonStats => vec![tables::pokemon_stats(dex)],
+            Table::PokemonAbilities => vec![tables::pokemon_abilities(dex)],
+            Table::PokemonTypes => vec![tables::pokemon_types(dex)],
+            Table::PokemonWeaknesses => vec![tables::pokemon_weaknesses(dex)],
+            Table::PokemonDefenses => vec![tables::pokemon_defenses(dex)],
+            Table::TypeEfficacy => vec![tables::type_efficacy(dex)],
+            Table::Moves => vec![tables::moves(dex, &config.move_filter)],
+            Table::MoveNames => vec![tables::move_names(dex)],
+            Table::Evolutions => vec![tables::evolutions(dex)],
+            Table::Learnsets => {
+                let version_group = config
+                    .version_group
+                    .or_else(|| dex.version_groups().last().copied())
+                    .unwrap_or_default();
+                vec![tables::learnsets(dex, version_group, &config.learn_methods)]
+            }
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct PokemonType {
-    #[serde(rename = "pokemon_id")]
-    id: u64,
-    type_id: u64,
-    slot: u64,
+#[derive(Debug, PartialEq)]
+struct Config {
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    on_bad_row: OnBadRow,
+    formats: Vec<Format>,
+    combined: bool,
+    generation: Option<Generation>,
+    /// Fallback chains of the names tables, one table each.
+    languages: Vec<Vec<Language>>,
+    forms: FormLayout,
+    move_filter: MoveFilter,
+    /// `None` for the latest version group of `pokemon_moves.csv`.
+    version_group: Option<u64>,
+    learn_methods: Vec<LearnMethod>,
+    tables: Vec<Table>,
 }
 
-#[derive(Debug, Deserialize)]
-struct TypeEfficacy {
-    #[serde(rename = "damage_type_id")]
-    damage: u64,
-    #[serde(rename = "target_type_id")]
-    target: u64,
-    #[serde(rename = "damage_factor")]
-    factor: u64,
+#[derive(Debug, PartialEq)]
+struct CoverageConfig {
+    input_dir: PathBuf,
+    on_bad_row: OnBadRow,
+    types: Vec<Type>,
+    moves: Vec<u64>,
 }
 
-#[derive(Debug, Deserialize)]
-struct Move {
-    id: u64,
-    type_id: u64,
-    power: Option<u64>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    Table,
+    Csv,
+    Json,
 }
 
-#[derive(Debug, Deserialize)]
-struct MoveName {
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("unknown report format `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TeamConfig {
+    input_dir: PathBuf,
+    on_bad_row: OnBadRow,
+    format: ReportFormat,
+    members: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct DamageConfig {
+    input_dir: PathBuf,
+    on_bad_row: OnBadRow,
+    attacker: String,
     move_id: u64,
-    #[serde(rename = "local_language_id")]
-    language: u64,
-    name: String,
+    defender: String,
+    level: u64,
+    defender_level: u64,
+    critical: bool,
 }
 
-fn process_pokemon_names(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+enum Command {
+    Run(Config),
+    Coverage(CoverageConfig),
+    TeamReport(TeamConfig),
+    Damage(DamageConfig),
+    /// Path of the manifest, read and validated by [`load_manifest`].
+    Manifest(PathBuf),
+    Help,
+}
 
-    let pokemon_names = reader.deserialize::<Pokemon>().map(Result::unwrap);
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "coverage").is_some() {
+        return parse_coverage_args(args);
+    }
+    if args.next_if(|arg| arg == "team-report").is_some() {
+        return parse_team_args(args);
+    }
+    if args.next_if(|arg| arg == "damage").is_some() {
+        return parse_damage_args(args);
+    }
+    if args.next_if(|arg| arg == "manifest").is_some() {
+        return parse_manifest_args(args);
+    }
 
-    pokemon_names
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let id = value.id;
-            // First letter is capitalized
-            let name = value.name[0..1].to_uppercase() + &value.name[1..];
-            acc.push_str(&format!("[{id}] = \"{name}\", \n"));
-            acc
-        })
+    let mut input_dir = PathBuf::from("csv");
+    let mut output_dir = PathBuf::from("output");
+    let mut on_bad_row = OnBadRow::Fail;
+    let mut formats: Vec<Format> = Vec::new();
+    let mut combined = false;
+    let mut generation = None;
+    let mut languages: Vec<Vec<Language>> = Vec::new();
+    let mut forms = FormLayout::default();
+    let mut move_filter = MoveFilter::damaging();
+    let mut version_group = None;
+    let mut learn_methods: Vec<LearnMethod> = Vec::new();
+    let mut tables: Vec<Table> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-i" | "--input-dir" => {
+                input_dir = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a directory"))?
+                    .into();
+            }
+            "-o" | "--output-dir" => {
+                output_dir = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a directory"))?
+                    .into();
+            }
+            "-f" | "--format" => {
+                let format = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a format"))?
+                    .parse()?;
+                if !formats.contains(&format) {
+                    formats.push(format);
+                }
+            }
+            "--combined" => combined = true,
+            "-g" | "--generation" => {
+                generation = Some(
+                    args.next()
+                        .ok_or_else(|| format!("{arg} requires a generation"))?
+                        .parse()?,
+                );
+            }
+            "--language" => {
+                let chain = language::parse_chain(
+                    &args
+                        .next()
+                        .ok_or_else(|| format!("{arg} requires a language"))?,
+                )?;
+                // Tables are named after the first language of the chain
+                if languages.iter().any(|other| other[0] == chain[0]) {
+                    return Err(format!("several language chains start with `{}`", chain[0]));
+                }
+                languages.push(chain);
+            }
+            "--forms" => {
+                forms = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a layout"))?
+                    .parse()?;
+            }
+            "--damage-class" => {
+                let class = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a damage class"))?
+                    .parse()?;
+                set_damage_class(&mut move_filter, class);
+            }
+            "--move-generation" => {
+                (move_filter.min_generation, move_filter.max_generation) =
+                    parse_range(&arg, args.next())?;
+            }
+            "--min-accuracy" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a value"))?;
+                move_filter.min_accuracy = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid accuracy `{value}`"))?,
+                );
+            }
+            "--priority" => {
+                (move_filter.min_priority, move_filter.max_priority) =
+                    parse_range(&arg, args.next())?;
+            }
+            "--version-group" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a version group"))?;
+                version_group = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid version group `{value}`"))?,
+                );
+            }
+            "--learn-method" => {
+                let method = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a learn method"))?
+                    .parse()?;
+                if !learn_methods.contains(&method) {
+                    learn_methods.push(method);
+                }
+            }
+            "--skip-bad-rows" => on_bad_row = OnBadRow::SkipAndWarn,
+            "all" => tables.extend(Table::ALL),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => tables
+                .push(Table::from_command(&arg).ok_or_else(|| format!("unknown table `{arg}`"))?),
+        }
+    }
+
+    if tables.is_empty() {
+        return Err("no table selected".to_owned());
+    }
+    if formats.is_empty() {
+        formats.push(Format::Lua);
+    }
+    if combined && !formats.contains(&Format::Lua) {
+        return Err("--combined requires the lua format".to_owned());
+    }
+
+    // Keep the first occurrence so `all pokemon-names` does not write twice
+    let mut selected: Vec<Table> = Vec::new();
+    for table in tables {
+        if !selected.contains(&table) {
+            selected.push(table);
+        }
+    }
+
+    Ok(Command::Run(Config {
+        input_dir,
+        output_dir,
+        on_bad_row,
+        formats,
+        combined,
+        generation,
+        languages,
+        forms,
+        move_filter,
+        version_group,
+        learn_methods,
+        tables: selected,
+    }))
 }
 
-fn process_pokemon_types(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+fn set_damage_class(move_filter: &mut MoveFilter, class: DamageClass) {
+    // Status moves never have a base power
+    move_filter.damaging_only = class != DamageClass::Status;
+    move_filter.damage_class = Some(class);
+}
 
-    let pokemon_types: Vec<PokemonType> = reader
-        .deserialize::<PokemonType>()
-        .map(Result::unwrap)
-        .collect();
+/// Parses `N`, `MIN..`, `..MAX` or `MIN..MAX` into optional bounds.
+fn parse_range<T: std::str::FromStr + Copy>(
+    arg: &str,
+    value: Option<String>,
+) -> Result<(Option<T>, Option<T>), String> {
+    let value = value.ok_or_else(|| format!("{arg} requires a range"))?;
+    let invalid = || format!("invalid range `{value}` for {arg}");
+    let bound = |bound: &str| -> Result<Option<T>, String> {
+        match bound {
+            "" => Ok(None),
+            _ => bound.parse().map(Some).map_err(|_| invalid()),
+        }
+    };
 
-    let mut pokemon_types_tree: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+    match value.split_once("..") {
+        Some((min, max)) => Ok((bound(min)?, bound(max)?)),
+        None => {
+            let exact = bound(&value)?;
+            Ok((exact, exact))
+        }
+    }
+}
 
-    pokemon_types.iter().for_each(|pokemon| match pokemon.slot {
-        1 => {
-            pokemon_types_tree.insert(pokemon.id, (pokemon.type_id, EMPTY_TYPE));
+fn parse_coverage_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut input_dir = PathBuf::from("csv");
+    let mut on_bad_row = OnBadRow::Fail;
+    let mut types: Vec<Type> = Vec::new();
+    let mut moves: Vec<u64> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-i" | "--input-dir" => {
+                input_dir = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a directory"))?
+                    .into();
+            }
+            "-m" | "--move" => {
+                let id = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a move id"))?;
+                moves.push(id.parse().map_err(|_| format!("invalid move id `{id}`"))?);
+            }
+            "--skip-bad-rows" => on_bad_row = OnBadRow::SkipAndWarn,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => types.push(arg.parse()?),
         }
-        2 => {
-            pokemon_types_tree.insert(
-                pokemon.id,
-                (pokemon_types_tree[&pokemon.id].0, pokemon.type_id),
-            );
+    }
+
+    if types.is_empty() && moves.is_empty() {
+        return Err("no attacking type or move given".to_owned());
+    }
+
+    Ok(Command::Coverage(CoverageConfig {
+        input_dir,
+        on_bad_row,
+        types,
+        moves,
+    }))
+}
+
+fn parse_team_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut input_dir = PathBuf::from("csv");
+    let mut on_bad_row = OnBadRow::Fail;
+    let mut format = ReportFormat::Table;
+    let mut members: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-i" | "--input-dir" => {
+                input_dir = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a directory"))?
+                    .into();
+            }
+            "-f" | "--format" => {
+                format = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a format"))?
+                    .parse()?;
+            }
+            "--skip-bad-rows" => on_bad_row = OnBadRow::SkipAndWarn,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => members.push(arg),
         }
-        _ => (),
-    });
-
-    pokemon_types_tree
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let id = value.0;
-            let first_type = value.1 .0;
-            let second_type = value.1 .1;
-            acc.push_str(&format!("[{id}] = {{{first_type}, {second_type}}}, \n"));
-            acc
-        })
+    }
+
+    if members.is_empty() {
+        return Err("no pokemon given".to_owned());
+    }
+    if members.len() > MAX_TEAM_SIZE {
+        return Err(format!(
+            "a team has at most {MAX_TEAM_SIZE} pokemon, got {}",
+            members.len()
+        ));
+    }
+
+    Ok(Command::TeamReport(TeamConfig {
+        input_dir,
+        on_bad_row,
+        format,
+        members,
+    }))
 }
 
-fn process_type_efficacy(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+fn parse_level(arg: &str, value: Option<String>) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{arg} requires a level"))?;
+    match value.parse() {
+        Ok(level @ 1..=100) => Ok(level),
+        _ => Err(format!("invalid level `{value}`, expected 1 to 100")),
+    }
+}
 
-    let type_efficacy: Vec<TypeEfficacy> = reader
-        .deserialize::<TypeEfficacy>()
-        .map(Result::unwrap)
-        .collect();
+fn parse_damage_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut input_dir = PathBuf::from("csv");
+    let mut on_bad_row = OnBadRow::Fail;
+    let mut level = 50;
+    let mut defender_level = None;
+    let mut critical = false;
+    let mut positional: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-i" | "--input-dir" => {
+                input_dir = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a directory"))?
+                    .into();
+            }
+            "-l" | "--level" => level = parse_level(&arg, args.next())?,
+            "--defender-level" => defender_level = Some(parse_level(&arg, args.next())?),
+            "--critical" => critical = true,
+            "--skip-bad-rows" => on_bad_row = OnBadRow::SkipAndWarn,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => positional.push(arg),
+        }
+    }
 
-    let mut type_efficacy_tree: BTreeMap<u64, BTreeMap<u64, u64>> = BTreeMap::new();
+    let [attacker, move_id, defender]: [String; 3] = positional
+        .try_into()
+        .map_err(|_| "expected an attacker, a move id and a defender".to_owned())?;
+    let move_id = move_id
+        .parse()
+        .map_err(|_| format!("invalid move id `{move_id}`"))?;
+
+    Ok(Command::Damage(DamageConfig {
+        input_dir,
+        on_bad_row,
+        attacker,
+        move_id,
+        defender,
+        level,
+        defender_level: defender_level.unwrap_or(level),
+        critical,
+    }))
+}
 
-    for efficacy in &type_efficacy {
-        if type_efficacy_tree.get(&efficacy.damage).is_none() {
-            type_efficacy_tree.insert(efficacy.damage, BTreeMap::default());
+fn parse_manifest_args<I: Iterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut path = None;
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ if path.is_some() => return Err(format!("unexpected argument `{arg}`")),
+            _ => path = Some(PathBuf::from(arg)),
         }
+    }
+    path.map(Command::Manifest)
+        .ok_or_else(|| "no manifest given".to_owned())
+}
+
+/// A manifest as written. Paths are relative to the manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    input_dir: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    #[serde(default)]
+    skip_bad_rows: bool,
+    #[serde(default, rename = "export")]
+    exports: Vec<ManifestEntry>,
+}
+
+/// An `[[export]]` entry as written, with the options of the command line.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestEntry {
+    table: String,
+    /// Relative to the output directory.
+    target: PathBuf,
+    /// Replaces the input file of single-input tables.
+    source: Option<PathBuf>,
+    /// Guessed from the extension of `target` when missing.
+    format: Option<String>,
+    language: Option<String>,
+    generation: Option<u64>,
+    forms: Option<String>,
+    damage_class: Option<String>,
+    move_generation: Option<String>,
+    min_accuracy: Option<u64>,
+    priority: Option<String>,
+    version_group: Option<u64>,
+    #[serde(default)]
+    learn_methods: Vec<String>,
+}
+
+/// A validated manifest, with paths resolved.
+#[derive(Debug, PartialEq)]
+struct Manifest {
+    on_bad_row: OnBadRow,
+    exports: Vec<Export>,
+}
+
+/// One table written to `target`.
+#[derive(Debug, PartialEq)]
+struct Export {
+    /// Selects exactly one table, format and at most one language chain.
+    config: Config,
+    source: Option<PathBuf>,
+    target: PathBuf,
+}
 
-        type_efficacy_tree
-            .get_mut(&efficacy.damage)
-            .map(|val| val.insert(efficacy.target, efficacy.factor));
+/// Reads and validates a manifest, errors naming the offending export.
+fn load_manifest(path: &Path) -> Result<Manifest, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let file: ManifestFile =
+        toml::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+    if file.exports.is_empty() {
+        return Err(format!("{}: no export", path.display()));
     }
 
-    type_efficacy_tree
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let damage_id = value.0;
-            let values = value.1.into_iter().fold(String::new(), |mut acc, val| {
-                let target_id = val.0;
-                let amount = val.1;
-                acc.push_str(&format!("\t[{target_id}] = {amount}, \n"));
-                acc
-            });
-            acc.push_str(&format!("[{damage_id}] = {{\n{values}}}, \n"));
-            acc
-        })
+    let base = path.parent().unwrap_or(Path::new(""));
+    let input_dir = base.join(file.input_dir.as_deref().unwrap_or(Path::new("csv")));
+    let output_dir = base.join(file.output_dir.as_deref().unwrap_or(Path::new("output")));
+    let on_bad_row = if file.skip_bad_rows {
+        OnBadRow::SkipAndWarn
+    } else {
+        OnBadRow::Fail
+    };
+
+    let mut exports: Vec<Export> = Vec::new();
+    for (index, entry) in file.exports.into_iter().enumerate() {
+        let name = format!(
+            "{}: export {} (`{}`)",
+            path.display(),
+            index + 1,
+            entry.target.display()
+        );
+        let export = parse_export(entry, base, &input_dir, &output_dir, on_bad_row)
+            .map_err(|err| format!("{name}: {err}"))?;
+        if let Some(other) = exports
+            .iter()
+            .position(|other| other.target == export.target)
+        {
+            return Err(format!("{name}: same target as export {}", other + 1));
+        }
+        exports.push(export);
+    }
+
+    Ok(Manifest {
+        on_bad_row,
+        exports,
+    })
 }
 
-fn process_moves(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+fn parse_export(
+    entry: ManifestEntry,
+    base: &Path,
+    input_dir: &Path,
+    output_dir: &Path,
+    on_bad_row: OnBadRow,
+) -> Result<Export, String> {
+    let table = Table::from_command(&entry.table)
+        .ok_or_else(|| format!("unknown table `{}`", entry.table))?;
+    let applies = |key: &str, set: bool, allowed: bool| -> Result<(), String> {
+        if set && !allowed {
+            return Err(format!("`{key}` does not apply to {}", table.command()));
+        }
+        Ok(())
+    };
+    let is_names = matches!(table, Table::PokemonNames | Table::MoveNames);
+    applies(
+        "source",
+        entry.source.is_some(),
+        table.input_files().len() == 1,
+    )?;
+    applies("language", entry.language.is_some(), is_names)?;
+    applies("forms", entry.forms.is_some(), table.is_keyed_by_pokemon())?;
+    for (key, set) in [
+        ("damage_class", entry.damage_class.is_some()),
+        ("move_generation", entry.move_generation.is_some()),
+        ("min_accuracy", entry.min_accuracy.is_some()),
+        ("priority", entry.priority.is_some()),
+    ] {
+        applies(key, set, table == Table::Moves)?;
+    }
+    applies(
+        "version_group",
+        entry.version_group.is_some(),
+        table == Table::Learnsets,
+    )?;
+    applies(
+        "learn_methods",
+        !entry.learn_methods.is_empty(),
+        table == Table::Learnsets,
+    )?;
+
+    if entry.target.as_os_str().is_empty() {
+        return Err("empty target".to_owned());
+    }
+    let format = match &entry.format {
+        Some(format) => format.parse()?,
+        None => {
+            let extension = entry.target.extension().and_then(|ext| ext.to_str());
+            Format::ALL
+                .into_iter()
+                .find(|format| Some(format.emitter().extension()) == extension)
+                .ok_or_else(|| "no format given and the target has no known extension".to_owned())?
+        }
+    };
 
-    let moves = reader
-        .deserialize::<Move>()
-        .map(std::result::Result::unwrap);
+    let mut move_filter = MoveFilter::damaging();
+    if let Some(class) = &entry.damage_class {
+        set_damage_class(&mut move_filter, class.parse()?);
+    }
+    if let Some(range) = entry.move_generation {
+        (move_filter.min_generation, move_filter.max_generation) =
+            parse_range("move_generation", Some(range))?;
+    }
+    move_filter.min_accuracy = entry.min_accuracy;
+    if let Some(range) = entry.priority {
+        (move_filter.min_priority, move_filter.max_priority) =
+            parse_range("priority", Some(range))?;
+    }
 
-    let attack_moves: Vec<Move> = moves
-        .into_iter()
-        .filter(|move_| move_.power.is_some())
-        .collect();
+    let mut learn_methods: Vec<LearnMethod> = Vec::new();
+    for method in &entry.learn_methods {
+        let method = method.parse()?;
+        if !learn_methods.contains(&method) {
+            learn_methods.push(method);
+        }
+    }
 
-    let mut type_moves_tree: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+    let config = Config {
+        input_dir: input_dir.to_owned(),
+        output_dir: output_dir.to_owned(),
+        on_bad_row,
+        formats: vec![format],
+        combined: false,
+        generation: entry
+            .generation
+            .map(|generation| generation.to_string().parse())
+            .transpose()?,
+        languages: match &entry.language {
+            Some(chain) => vec![language::parse_chain(chain)?],
+            None => Vec::new(),
+        },
+        forms: match &entry.forms {
+            Some(layout) => layout.parse()?,
+            None => FormLayout::default(),
+        },
+        move_filter,
+        version_group: entry.version_group,
+        learn_methods,
+        tables: vec![table],
+    };
+    Ok(Export {
+        config,
+        source: entry.source.map(|source| base.join(source)),
+        target: output_dir.join(entry.target),
+    })
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    }
+}
 
-    for move_ in &attack_moves {
-        if type_moves_tree.get(&move_.type_id).is_none() {
-            type_moves_tree.insert(move_.type_id, Vec::default());
+fn write_to_file(path: &Path, buf: &str) -> error::Result<()> {
+    fs::write(path, buf).map_err(io_error(path))
+}
+
+/// Writes a report to stdout, where the reader having enough, as with
+/// `| head`, is not an error.
+fn print_report(report: &str) -> error::Result<()> {
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(report.as_bytes())
+        .and_then(|()| stdout.flush())
+    {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+            Err(io_error(Path::new("<stdout>"))(err))
         }
+        _ => Ok(()),
+    }
+}
 
-        if let Some(val) = type_moves_tree.get_mut(&move_.type_id) {
-            val.push(move_.id);
+/// Inputs refining `--generation` when they are in the input directory.
+const GENERATION_OPTIONAL_FILES: [&str; 2] = ["pokemon_species.csv", "pokemon_types_past.csv"];
+
+fn load_file(
+    dex: &mut Pokedex,
+    file: &str,
+    input: &CsvSource,
+    diagnostics: &mut Diagnostics,
+) -> error::Result<()> {
+    match file {
+        "pokemon.csv" => dex.load_pokemon(input, diagnostics),
+        "pokemon_species.csv" => dex.load_pokemon_species(input, diagnostics),
+        "pokemon_species_names.csv" => dex.load_pokemon_species_names(input, diagnostics),
+        "pokemon_evolution.csv" => dex.load_pokemon_evolution(input, diagnostics),
+        "pokemon_types.csv" => dex.load_pokemon_types(input, diagnostics),
+        "pokemon_types_past.csv" => dex.load_pokemon_types_past(input, diagnostics),
+        "pokemon_stats.csv" => dex.load_pokemon_stats(input, diagnostics),
+        "pokemon_abilities.csv" => dex.load_pokemon_abilities(input, diagnostics),
+        "type_efficacy.csv" => dex.load_type_efficacy(input, diagnostics),
+        "moves.csv" => dex.load_moves(input, diagnostics),
+        "move_names.csv" => dex.load_move_names(input, diagnostics),
+        "pokemon_moves.csv" => dex.load_pokemon_moves(input, diagnostics),
+        _ => unreachable!("no loader for {file}"),
+    }
+}
+
+/// Loads the inputs of `config`, reading `source` instead of the input of a
+/// single-input table when given.
+fn load_dex(
+    config: &Config,
+    source: Option<&Path>,
+    diagnostics: &mut Diagnostics,
+) -> error::Result<Pokedex> {
+    let mut files: Vec<&str> = Vec::new();
+    for table in &config.tables {
+        files.extend(table.input_files());
+        if config.generation.is_some() {
+            files.extend(table.generation_input_file());
+        }
+        if !config.languages.is_empty() {
+            files.extend(table.language_input_file());
         }
+        if config.forms != FormLayout::AllForms && table.is_keyed_by_pokemon() {
+            files.push("pokemon.csv");
+        }
+    }
+    if config.generation.is_some() {
+        files.extend(
+            GENERATION_OPTIONAL_FILES
+                .into_iter()
+                .filter(|file| config.input_dir.join(file).is_file()),
+        );
     }
+    // The pokemon-types, -weaknesses and -defenses tables share their input
+    let files: Vec<&str> = files.into_iter().unique().collect();
+
+    let mut dex = Pokedex::new();
+    for file in files {
+        let path = match source {
+            Some(source)
+                if config
+                    .tables
+                    .iter()
+                    .any(|table| table.input_files() == [file]) =>
+            {
+                source.to_owned()
+            }
+            _ => config.input_dir.join(file),
+        };
+        let input = CsvSource::open(path)?;
+        load_file(&mut dex, file, &input, diagnostics)?;
+    }
+    if let Some(generation) = config.generation {
+        dex.restrict_to_generation(generation);
+    }
+    Ok(dex)
+}
 
-    type_moves_tree
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let type_ = value.0;
-            let moves = value.1.iter().join(", ");
-            acc.push_str(&format!("[{type_}] = {{ {moves} }}, \n"));
-            acc
+fn build_tables(config: &Config, dex: &Pokedex) -> Vec<emit::Table> {
+    config
+        .tables
+        .iter()
+        .flat_map(|table| {
+            let built = table.build(dex, config);
+            if !table.is_keyed_by_pokemon() {
+                return built;
+            }
+            built
+                .into_iter()
+                .map(|built| tables::with_forms(built, dex, config.forms))
+                .collect()
         })
+        .collect()
 }
 
-fn process_move_names(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+fn run(config: &Config, diagnostics: &mut Diagnostics) -> error::Result<()> {
+    fs::create_dir_all(&config.output_dir).map_err(io_error(&config.output_dir))?;
 
-    let moves = reader.deserialize::<MoveName>().map(Result::unwrap);
+    let dex = load_dex(config, None, diagnostics)?;
+    let tables = build_tables(config, &dex);
 
-    let move_names: BTreeMap<u64, String> = moves
-        .into_iter()
-        .filter(|move_| move_.language == 8)
-        .fold(BTreeMap::new(), |mut acc, value| {
-            acc.entry(value.move_id).or_insert(value.name);
-            acc
-        });
+    for format in &config.formats {
+        if *format == Format::Lua && config.combined {
+            let path = config.output_dir.join("pokedex.lua");
+            write_to_file(&path, &Lua.emit_combined("pokedex", &tables))?;
+            continue;
+        }
 
-    move_names
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let id = value.0;
-            let name = &value.1;
-            acc.push_str(&format!("[{id}] = \"{name}\", \n"));
-            acc
-        })
+        let emitter = format.emitter();
+        for table in &tables {
+            let file_name = format!("{}.{}", table.name, emitter.extension());
+            write_to_file(&config.output_dir.join(file_name), &emitter.emit(table))?;
+        }
+    }
+
+    Ok(())
 }
 
-fn process_pokemon_weaknesses(input: &str) -> String {
-    let mut reader = Reader::from_reader(input.as_bytes());
+/// Writes the table of every export, loading the inputs of each on its own.
+fn run_manifest(manifest: &Manifest, diagnostics: &mut Diagnostics) -> error::Result<()> {
+    for export in &manifest.exports {
+        let config = &export.config;
+        let dex = load_dex(config, export.source.as_deref(), diagnostics)?;
+        // An export has one table and at most one language chain
+        let [table]: [emit::Table; 1] = build_tables(config, &dex)
+            .try_into()
+            .expect("an export builds a single table");
+
+        if let Some(dir) = export.target.parent() {
+            fs::create_dir_all(dir).map_err(io_error(dir))?;
+        }
+        write_to_file(&export.target, &config.formats[0].emitter().emit(&table))?;
+    }
 
-    let pokemon_types: Vec<PokemonType> = reader
-        .deserialize::<PokemonType>()
-        .map(Result::unwrap)
-        .collect();
+    Ok(())
+}
 
-    let mut pokemon_types_tree: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+/// The coverage report, one line per defending combination followed by the walls.
+fn run_coverage(config: &CoverageConfig, diagnostics: &mut Diagnostics) -> error::Result<String> {
+    let mut dex = Pokedex::new();
+    let mut types = config.types.clone();
+    if !config.moves.is_empty() {
+        let input = CsvSource::open(config.input_dir.join("moves.csv"))?;
+        dex.load_moves(&input, diagnostics)?;
+        for id in &config.moves {
+            types.push(dex.move_type(*id).ok_or_else(|| Error::NotFound {
+                kind: "move",
+                key: id.to_string(),
+            })?);
+        }
+    }
 
-    pokemon_types.iter().for_each(|pokemon| match pokemon.slot {
-        1 => {
-            pokemon_types_tree.insert(pokemon.id, (pokemon.type_id, 0));
+    let coverage = Coverage::new(dex.type_chart(), &types);
+    let mut report = format!("Coverage of {}\n\n", join(&coverage.attacking));
+    for matchup in &coverage.matchups {
+        let best = matchup.best.map_or("-".to_owned(), |best| best.to_string());
+        let line = format!("{:<20}{best:>6}  {}", matchup.defending, join(&matchup.by));
+        report += line.trim_end();
+        report.push('\n');
+    }
+
+    let super_effective = coverage.super_effective().count();
+    let walls: Vec<_> = coverage.walls().collect();
+    report += &format!(
+        "\nSuper effective against {super_effective} of {} combinations\n",
+        coverage.matchups.len()
+    );
+    if walls.is_empty() {
+        report += "Nothing resists every attacking type\n";
+    } else {
+        report += &format!("Resisting every attacking type ({}):\n", walls.len());
+        for matchup in walls {
+            // Walls always have a best multiplier
+            let best = matchup.best.unwrap_or_default();
+            report += &format!("    {:<20}{best:>6}\n", matchup.defending);
         }
-        2 => {
-            pokemon_types_tree.insert(
-                pokemon.id,
-                (pokemon_types_tree[&pokemon.id].0, pokemon.type_id),
+    }
+    Ok(report)
+}
+
+fn run_team_report(config: &TeamConfig, diagnostics: &mut Diagnostics) -> error::Result<String> {
+    let mut dex = Pokedex::new();
+    dex.load_pokemon(
+        &CsvSource::open(config.input_dir.join("pokemon.csv"))?,
+        diagnostics,
+    )?;
+    dex.load_pokemon_types(
+        &CsvSource::open(config.input_dir.join("pokemon_types.csv"))?,
+        diagnostics,
+    )?;
+
+    let mut members = Vec::new();
+    for key in &config.members {
+        let pokemon = find_pokemon(&dex, key)?;
+        let types = dex.types_of(pokemon.id).ok_or_else(|| Error::NotFound {
+            kind: "types for pokemon",
+            key: key.clone(),
+        })?;
+        members.push(Member {
+            pokemon_id: pokemon.id,
+            identifier: pokemon.name.clone(),
+            types,
+        });
+    }
+
+    let report = TeamReport::new(dex.type_chart(), members);
+    Ok(match config.format {
+        ReportFormat::Table => report.to_table(),
+        ReportFormat::Csv => report.to_csv(),
+        ReportFormat::Json => report.to_json(),
+    })
+}
+
+fn run_damage(config: &DamageConfig, diagnostics: &mut Diagnostics) -> error::Result<String> {
+    let mut dex = Pokedex::new();
+    dex.load_pokemon(
+        &CsvSource::open(config.input_dir.join("pokemon.csv"))?,
+        diagnostics,
+    )?;
+    dex.load_pokemon_types(
+        &CsvSource::open(config.input_dir.join("pokemon_types.csv"))?,
+        diagnostics,
+    )?;
+    dex.load_pokemon_stats(
+        &CsvSource::open(config.input_dir.join("pokemon_stats.csv"))?,
+        diagnostics,
+    )?;
+    dex.load_moves(
+        &CsvSource::open(config.input_dir.join("moves.csv"))?,
+        diagnostics,
+    )?;
+
+    let combatant = |key: &str, level: u64| -> error::Result<(&Pokemon, Combatant)> {
+        let pokemon = find_pokemon(&dex, key)?;
+        let not_found = |kind| Error::NotFound {
+            kind,
+            key: key.to_owned(),
+        };
+        let types = dex
+            .types_of(pokemon.id)
+            .ok_or_else(|| not_found("types for pokemon"))?;
+        let base_stats = dex
+            .base_stats(pokemon.id)
+            .ok_or_else(|| not_found("stats for pokemon"))?;
+        Ok((pokemon, Combatant::new(base_stats, types, level)))
+    };
+    let (attacker, attacking) = combatant(&config.attacker, config.level)?;
+    let (defender, defending) = combatant(&config.defender, config.defender_level)?;
+    let move_ = dex
+        .move_by_id(config.move_id)
+        .ok_or_else(|| Error::NotFound {
+            kind: "move",
+            key: config.move_id.to_string(),
+        })?;
+
+    let mut report = format!(
+        "{} (level {}) using move {} on {} (level {})\n",
+        attacker.name, config.level, move_.id, defender.name, config.defender_level
+    );
+    match damage::calculate(
+        dex.type_chart(),
+        &attacking,
+        &defending,
+        move_,
+        config.critical,
+    ) {
+        Some(range) => {
+            report += &format!(
+                "{}-{} damage of {} HP ({:.1}% - {:.1}%), {} effective{}\n",
+                range.min,
+                range.max,
+                range.target_hp,
+                range.min_percent(),
+                range.max_percent(),
+                range.effectiveness,
+                if config.critical {
+                    ", critical hit"
+                } else {
+                    ""
+                }
             );
         }
-        _ => (),
-    });
+        None => report += "The move deals no direct damage\n",
+    }
+    Ok(report)
+}
+
+/// Looks a Pokemon up by id, or by identifier whatever its case.
+fn find_pokemon<'a>(dex: &'a Pokedex, key: &str) -> error::Result<&'a Pokemon> {
+    match key.parse::<u64>() {
+        Ok(id) => dex.pokemon_by_id(id),
+        Err(_) => dex.pokemon_by_identifier(&key.to_lowercase()),
+    }
+    .ok_or_else(|| Error::NotFound {
+        kind: "pokemon",
+        key: key.to_owned(),
+    })
+}
 
-    let mut index_weaknesses_tree: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+fn join(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(Type::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    for pokemon in &pokemon_types_tree {
-        let mut weak_types: Vec<u64> = Vec::new();
-        let pokemon_id = *pokemon.0;
-        let first_type = &pokemon.1 .0;
-        let second_type = &pokemon.1 .1;
-        get_type_efficacy()
-            .iter()
-            .for_each(|type_| match second_type {
-                &EMPTY_TYPE => {
-                    if type_.1[first_type] == SUPER_EFFECTIVE {
-                        weak_types.push(*type_.0);
-                    }
-                }
-                _ => {
-                    if type_.1[first_type] * type_.1[second_type] > (EFFECTIVE * EFFECTIVE) {
-                        weak_types.push(*type_.0);
-                    }
+fn main() -> ExitCode {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let (mut diagnostics, result) = match command {
+        Command::Help => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Command::Run(config) => {
+            let mut diagnostics = Diagnostics::new(config.on_bad_row);
+            let result = run(&config, &mut diagnostics);
+            (diagnostics, result)
+        }
+        Command::Coverage(config) => {
+            let mut diagnostics = Diagnostics::new(config.on_bad_row);
+            let result =
+                run_coverage(&config, &mut diagnostics).and_then(|report| print_report(&report));
+            (diagnostics, result)
+        }
+        Command::Damage(config) => {
+            let mut diagnostics = Diagnostics::new(config.on_bad_row);
+            let result =
+                run_damage(&config, &mut diagnostics).and_then(|report| print_report(&report));
+            (diagnostics, result)
+        }
+        Command::Manifest(path) => {
+            let manifest = match load_manifest(&path) {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    return ExitCode::from(2);
                 }
-            });
-        index_weaknesses_tree.insert(pokemon_id, weak_types);
+            };
+            let mut diagnostics = Diagnostics::new(manifest.on_bad_row);
+            let result = run_manifest(&manifest, &mut diagnostics);
+            (diagnostics, result)
+        }
+        Command::TeamReport(config) => {
+            let mut diagnostics = Diagnostics::new(config.on_bad_row);
+            let result =
+                run_team_report(&config, &mut diagnostics).and_then(|report| print_report(&report));
+            (diagnostics, result)
+        }
+    };
+
+    for warning in diagnostics.take_warnings() {
+        eprintln!("warning: skipped {warning}");
     }
 
-    index_weaknesses_tree
-        .into_iter()
-        .fold(String::new(), |mut acc, value| {
-            let id = value.0;
-            let weak_types = value.1.iter().join(", ");
-            acc.push_str(&format!("[{id}] = {{ {weak_types} }}, \n"));
-            acc
-        })
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn write_to_file(path: &str, buf: &str) {
-    let mut file = File::create(path).unwrap();
-    file.write_all(buf.as_bytes()).unwrap();
+/// The configuration of the command line with only a table given, for tests
+/// to override the fields they exercise.
+#[cfg(test)]
+fn test_config() -> Config {
+    Config {
+        input_dir: PathBuf::from("csv"),
+        output_dir: PathBuf::from("output"),
+        on_bad_row: OnBadRow::Fail,
+        formats: vec![Format::Lua],
+        combined: false,
+        generation: None,
+        languages: Vec::new(),
+        forms: FormLayout::AllForms,
+        move_filter: MoveFilter::damaging(),
+        version_group: None,
+        learn_methods: Vec::new(),
+        tables: Vec::new(),
+    }
 }
 
-fn main() {
-    // TODO: Refactor all of this with builder pattern
-    write_to_file(
-        "output/pokemon_names.txt",
-        &process_pokemon_names(include_str!("../../csv/pokemon.csv")),
-    );
-    write_to_file(
-        "output/pokemon_types.txt",
-        &process_pokemon_types(include_str!("../../csv/pokemon_types.csv")),
-    );
-    write_to_file(
-        "output/pokemon_weaknesses.txt",
-        &process_pokemon_weaknesses(include_str!("../../csv/pokemon_types.csv")),
-    );
-    write_to_file(
-        "output/type_efficacy.txt",
-        &process_type_efficacy(include_str!("../../csv/type_efficacy.csv")),
-    );
-    write_to_file(
-        "output/moves.txt",
-        &process_moves(include_str!("../../csv/moves.csv")),
-    );
-    write_to_file(
-        "output/move_names.txt",
-        &process_move_names(include_str!("../../csv/move_names.csv")),
-    );
+#[test]
+fn test_parse_args() {
+    let args = [
+        "-i",
+        "dump",
+        "--output-dir",
+        "lua",
+        "-f",
+        "lua",
+        "-f",
+        "json",
+        "--format",
+        "c",
+        "--combined",
+        "moves",
+        "pokemon-names",
+    ];
+
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(config.input_dir, PathBuf::from("dump"));
+    assert_eq!(config.output_dir, PathBuf::from("lua"));
+    assert_eq!(config.formats, vec![Format::Lua, Format::Json, Format::C]);
+    assert!(config.combined);
+    assert_eq!(config.tables, vec![Table::Moves, Table::PokemonNames]);
+}
+
+#[test]
+fn test_parse_args_all() {
+    let Ok(Command::Run(config)) = parse_args(["moves", "all"].map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(config.input_dir, PathBuf::from("csv"));
+    assert_eq!(config.output_dir, PathBuf::from("output"));
+    assert_eq!(config.formats, vec![Format::Lua]);
+    assert!(!config.combined);
+    assert_eq!(config.tables.len(), Table::ALL.len());
+    assert_eq!(config.tables[0], Table::Moves);
 }
 
 #[test]
-fn test_pokemon_names() {
-    let mut input = String::new();
-    input.push_str("id,identifier,species_id,height,weight,base_experience,order,is_default\n");
-    input.push_str("1,bulbasaur,1,7,69,64,1,1\n");
-    input.push_str("905,enamorus-incarnate,905,16,480,,,1\n");
+fn test_parse_args_errors() {
+    assert!(parse_args(Vec::new()).is_err());
+    assert!(parse_args(["pokemon-nmes"].map(String::from)).is_err());
+    assert!(parse_args(["--input-dir"].map(String::from)).is_err());
+    assert!(parse_args(["--verbose", "moves"].map(String::from)).is_err());
+    assert!(parse_args(["--format", "yaml", "moves"].map(String::from)).is_err());
+    assert!(parse_args(["-f", "json", "--combined", "moves"].map(String::from)).is_err());
+}
 
-    let mut output = String::new();
-    output.push_str("[1] = \"Bulbasaur\", \n");
-    output.push_str("[905] = \"Enamorus-incarnate\", \n");
+#[test]
+fn test_run_missing_input() {
+    let config = Config {
+        input_dir: PathBuf::from("does-not-exist"),
+        output_dir: env::temp_dir(),
+        tables: vec![Table::Moves],
+        ..test_config()
+    };
+
+    let err = run(&config, &mut Diagnostics::default())
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("does-not-exist"));
+    assert!(err.contains("moves.csv"));
+}
 
-    let result = process_pokemon_names(&input);
+#[test]
+fn test_parse_move_filter() {
+    let args = [
+        "--damage-class",
+        "physical",
+        "--move-generation",
+        "..4",
+        "--min-accuracy",
+        "90",
+        "--priority",
+        "-7..-1",
+        "moves",
+    ];
+
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(
+        config.move_filter,
+        MoveFilter {
+            damaging_only: true,
+            damage_class: Some(DamageClass::Physical),
+            min_generation: None,
+            max_generation: Some(4),
+            min_accuracy: Some(90),
+            min_priority: Some(-7),
+            max_priority: Some(-1),
+        }
+    );
 
-    assert_eq!(result, output);
+    let args = [
+        "--damage-class",
+        "status",
+        "--move-generation",
+        "3",
+        "moves",
+    ];
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+    assert!(!config.move_filter.damaging_only);
+    assert_eq!(config.move_filter.min_generation, Some(3));
+    assert_eq!(config.move_filter.max_generation, Some(3));
+
+    assert!(parse_args(["--damage-class", "shadow", "moves"].map(String::from)).is_err());
+    assert!(parse_args(["--move-generation", "4-5", "moves"].map(String::from)).is_err());
+    assert!(parse_args(["--priority", "1..x", "moves"].map(String::from)).is_err());
 }
 
 #[test]
-fn test_pokemon_types() {
-    let mut input = String::new();
-    input.push_str("pokemon_id,type_id,slot\n");
-    input.push_str("1,12,1\n");
-    input.push_str("1,4,2\n");
-    input.push_str("897,8,1\n");
+fn test_run_generation() {
+    let temp_dir = TempDir::new("test-run-generation");
+    let output_dir = temp_dir.path().join("output");
+    let config = Config {
+        output_dir: output_dir.clone(),
+        formats: vec![Format::Json],
+        generation: Generation::new(3),
+        tables: vec![Table::PokemonTypes, Table::TypeEfficacy, Table::MoveNames],
+        ..test_config()
+    };
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let types = fs::read_to_string(output_dir.join("pokemon_types.json")).unwrap();
+    assert!(types.contains("\"386\": [14, 0]"));
+    assert!(!types.contains("\"387\""));
+    let efficacy = fs::read_to_string(output_dir.join("type_efficacy.json")).unwrap();
+    assert!(!efficacy.contains("\"18\""));
+    let move_names = fs::read_to_string(output_dir.join("move_names.json")).unwrap();
+    assert!(move_names.contains("\"354\""));
+    assert!(!move_names.contains("\"355\""));
+
+    assert!(parse_args(["-g", "10", "moves"].map(String::from)).is_err());
+}
 
-    let mut output = String::new();
-    output.push_str("[1] = {12, 4}, \n");
-    output.push_str("[897] = {8, 0}, \n");
+#[test]
+fn test_parse_languages() {
+    let args = ["--language", "it,en", "--language", "9", "move-names"];
 
-    let result = process_pokemon_types(&input);
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
 
-    assert_eq!(result, output);
+    assert_eq!(
+        config.languages,
+        vec![vec![Language::It, Language::En], vec![Language::En]]
+    );
+    assert!(parse_args(["--language", "xx", "move-names"].map(String::from)).is_err());
+    assert!(
+        parse_args(["--language", "it", "--language", "it,en", "moves"].map(String::from)).is_err()
+    );
 }
 
 #[test]
-fn test_pokemon_weaknesses() {
-    let mut input = String::new();
-    input.push_str("pokemon_id,type_id,slot\n");
-    input.push_str("1,12,1\n");
-    input.push_str("1,4,2\n");
-    input.push_str("895,16,1\n");
+fn test_run_languages() {
+    let temp_dir = TempDir::new("test-run-languages");
+    let input_dir = temp_dir.path();
+    let output_dir = input_dir.join("output");
+    for file in ["pokemon.csv", "moves.csv", "move_names.csv"] {
+        fs::copy(Path::new("csv").join(file), input_dir.join(file)).unwrap();
+    }
+    fs::write(
+        input_dir.join("pokemon_species_names.csv"),
+        "pokemon_species_id,local_language_id,name,genus\n\
+         25,9,Pikachu,Mouse Pok\u{e9}mon\n\
+         122,5,M. Mime,\n",
+    )
+    .unwrap();
+    let config = Config {
+        input_dir: input_dir.to_owned(),
+        output_dir: output_dir.clone(),
+        formats: vec![Format::Json],
+        languages: vec![vec![Language::It, Language::En], vec![Language::Fr]],
+        tables: vec![Table::PokemonNames, Table::MoveNames],
+        ..test_config()
+    };
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let names = fs::read_to_string(output_dir.join("pokemon_names_it.json")).unwrap();
+    assert!(names.contains("\"25\": \"Pikachu\""));
+    assert!(names.contains("\"1\": \"Bulbasaur\""));
+    let names = fs::read_to_string(output_dir.join("pokemon_names_fr.json")).unwrap();
+    assert!(names.contains("\"122\": \"M. Mime\""));
+    let moves = fs::read_to_string(output_dir.join("move_names_it.json")).unwrap();
+    assert!(moves.contains("\"1\": \"Botta\""));
+    assert!(output_dir.join("move_names_fr.json").is_file());
+    assert!(!output_dir.join("move_names.json").exists());
+}
 
-    let mut output = String::new();
-    output.push_str("[1] = { 3, 10, 14, 15 }, \n");
-    output.push_str("[895] = { 15, 16, 18 }, \n");
+#[test]
+fn test_run_forms() {
+    let temp_dir = TempDir::new("test-run-forms");
+    let input_dir = temp_dir.path();
+    let output_dir = input_dir.join("output");
+    fs::write(
+        input_dir.join("pokemon.csv"),
+        "id,identifier,species_id,height,weight,base_experience,order,is_default\n\
+         26,raichu,26,8,300,243,35,1\n\
+         10100,raichu-alola,26,7,210,243,36,0\n",
+    )
+    .unwrap();
+    fs::write(
+        input_dir.join("pokemon_types.csv"),
+        "pokemon_id,type_id,slot\n26,13,1\n10100,13,1\n10100,14,2\n",
+    )
+    .unwrap();
+    let config = Config {
+        input_dir: input_dir.to_owned(),
+        output_dir: output_dir.clone(),
+        formats: vec![Format::Json],
+        forms: FormLayout::Species,
+        tables: vec![Table::PokemonTypes],
+        ..test_config()
+    };
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let types = fs::read_to_string(output_dir.join("pokemon_types.json")).unwrap();
+    assert!(types.contains("\"26\": [13, 0]"));
+    assert!(!types.contains("10100"));
+
+    let Ok(Command::Run(config)) = parse_args(["--forms", "nested", "all"].map(String::from))
+    else {
+        panic!("arguments should parse");
+    };
+    assert_eq!(config.forms, FormLayout::Nested);
+    assert!(parse_args(["--forms", "megas", "all"].map(String::from)).is_err());
+}
 
-    let result = process_pokemon_weaknesses(&input);
+#[test]
+fn test_run_evolutions() {
+    let temp_dir = TempDir::new("test-run-evolutions");
+    let input_dir = temp_dir.path();
+    let output_dir = input_dir.join("output");
+    fs::write(
+        input_dir.join("pokemon_species.csv"),
+        "id,identifier,generation_id,evolves_from_species_id\n\
+         133,eevee,1,\n\
+         134,vaporeon,1,133\n\
+         197,umbreon,2,133\n",
+    )
+    .unwrap();
+    fs::write(
+        input_dir.join("pokemon_evolution.csv"),
+        "id,evolved_species_id,evolution_trigger_id,trigger_item_id,minimum_level,time_of_day\n\
+         3,134,3,84,,\n\
+         4,197,1,,,night\n",
+    )
+    .unwrap();
+    let mut config = Config {
+        input_dir: input_dir.to_owned(),
+        output_dir: output_dir.clone(),
+        tables: vec![Table::Evolutions],
+        ..test_config()
+    };
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let lua = fs::read_to_string(output_dir.join("evolutions.lua")).unwrap();
+    assert!(lua.contains("\t[134] = { evolves_from = 133, evolves_to = {} },\n"));
+    assert!(lua.contains(
+        "species_id = 134, trigger = \"use-item\", minimum_level = 0, trigger_item_id = 84"
+    ));
+    assert!(lua.contains("species_id = 197, trigger = \"level-up\""));
+    assert!(lua.contains("time_of_day = \"night\""));
+
+    config.generation = Generation::new(1);
+    run(&config, &mut Diagnostics::default()).unwrap();
+    let lua = fs::read_to_string(output_dir.join("evolutions.lua")).unwrap();
+    assert!(!lua.contains("197"));
+}
 
-    assert_eq!(result, output);
+#[test]
+fn test_run_learnsets() {
+    let temp_dir = TempDir::new("test-run-learnsets");
+    let input_dir = temp_dir.path();
+    let output_dir = input_dir.join("output");
+    fs::write(
+        input_dir.join("pokemon_moves.csv"),
+        "pokemon_id,version_group_id,move_id,pokemon_move_method_id,level,order\n\
+         1,1,22,1,13,\n\
+         1,1,15,4,0,\n\
+         1,1,33,1,1,\n\
+         1,18,22,1,9,\n\
+         25,1,84,1,1,\n",
+    )
+    .unwrap();
+    let args = [
+        "-i",
+        input_dir.to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "-f",
+        "lua",
+        "-f",
+        "json",
+        "--version-group",
+        "1",
+        "--learn-method",
+        "level-up",
+        "learnsets",
+    ];
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+    assert_eq!(config.version_group, Some(1));
+    assert_eq!(config.learn_methods, [LearnMethod::LevelUp]);
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let lua = fs::read_to_string(output_dir.join("learnsets.lua")).unwrap();
+    assert!(lua.contains(
+        "\t[1] = { { move_id = 33, method = \"level-up\", level = 1 }, \
+         { move_id = 22, method = \"level-up\", level = 13 } },\n"
+    ));
+    assert!(lua.contains("[25] = { { move_id = 84"));
+    assert!(output_dir.join("learnsets.json").is_file());
+
+    // The latest version group by default
+    let config = Config {
+        version_group: None,
+        learn_methods: Vec::new(),
+        ..config
+    };
+    run(&config, &mut Diagnostics::default()).unwrap();
+    let lua = fs::read_to_string(output_dir.join("learnsets.lua")).unwrap();
+    assert!(lua.contains("\t[1] = { { move_id = 22, method = \"level-up\", level = 9 } },\n"));
+    assert!(!lua.contains("[25]"));
+
+    assert!(parse_args(["--learn-method", "hm", "learnsets"].map(String::from)).is_err());
+    assert!(parse_args(["--version-group", "x", "learnsets"].map(String::from)).is_err());
 }
 
 #[test]
-fn test_type_efficacy() {
-    let mut input = String::new();
-    input.push_str("damage_type_id,target_type_id,damage_factor\n");
-    input.push_str("1,1,100\n");
-    input.push_str("18,17,200\n");
+fn test_run_pokemon_data() {
+    let temp_dir = TempDir::new("test-run-pokemon-data");
+    let input_dir = temp_dir.path();
+    let output_dir = input_dir.join("output");
+    fs::copy("csv/pokemon.csv", input_dir.join("pokemon.csv")).unwrap();
+    let mut stats = String::from("pokemon_id,stat_id,base_stat,effort\n");
+    for (stat_id, base_stat) in [45, 49, 49, 65, 65, 45].iter().enumerate() {
+        stats.push_str(&format!("1,{},{base_stat},0\n", stat_id + 1));
+    }
+    fs::write(input_dir.join("pokemon_stats.csv"), stats).unwrap();
+    fs::write(
+        input_dir.join("pokemon_abilities.csv"),
+        "pokemon_id,ability_id,is_hidden,slot\n1,65,0,1\n1,34,1,3\n132,7,0,1\n",
+    )
+    .unwrap();
+    let args = [
+        "-i",
+        input_dir.to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "pokemon-attributes",
+        "pokemon-stats",
+        "pokemon-abilities",
+    ];
+    let Ok(Command::Run(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    run(&config, &mut Diagnostics::default()).unwrap();
+
+    let read = |name: &str| fs::read_to_string(output_dir.join(name)).unwrap();
+    let attributes = read("pokemon_attributes.lua");
+    assert!(attributes.contains("\t[1] = { height = 7, weight = 69, base_experience = 64 },\n"));
+    assert!(attributes.contains("\t[905] = { height = 16, weight = 480, base_experience = 0 },\n"));
+    assert!(read("pokemon_stats.lua").contains(
+        "\t[1] = { hp = 45, attack = 49, defense = 49, special_attack = 65, \
+         special_defense = 65, speed = 45 },\n"
+    ));
+    let abilities = read("pokemon_abilities.lua");
+    assert!(abilities.contains("\t[1] = { abilities = { 65 }, hidden_ability = 34 },\n"));
+    assert!(abilities.contains("\t[132] = { abilities = { 7 }, hidden_ability = 0 },\n"));
+}
 
-    let mut output = String::new();
-    output.push_str("[1] = {\n\t[1] = 100, \n}, \n");
-    output.push_str("[18] = {\n\t[17] = 200, \n}, \n");
+#[test]
+fn test_load_manifest() {
+    let manifest = load_manifest(Path::new("csv-to-lua-table.toml")).unwrap();
+
+    assert_eq!(manifest.on_bad_row, OnBadRow::Fail);
+    assert_eq!(manifest.exports.len(), 8);
+    let export = &manifest.exports[7];
+    assert_eq!(export.config.input_dir, PathBuf::from("csv"));
+    assert_eq!(export.config.tables, vec![Table::TypeEfficacy]);
+    assert_eq!(export.config.formats, vec![Format::C]);
+    assert_eq!(export.target, PathBuf::from("output/c/type_efficacy.h"));
+
+    let Ok(Command::Manifest(path)) = parse_args(["manifest", "a.toml"].map(String::from)) else {
+        panic!("arguments should parse");
+    };
+    assert_eq!(path, PathBuf::from("a.toml"));
+    assert!(parse_args(["manifest"].map(String::from)).is_err());
+    assert!(parse_args(["manifest", "a.toml", "b.toml"].map(String::from)).is_err());
+}
+
+#[test]
+fn test_manifest_errors() {
+    let temp_dir = TempDir::new("test-manifest-errors");
+    let dir = temp_dir.path();
+    let path = dir.join("manifest.toml");
+    let error = |manifest: &str| {
+        fs::write(&path, manifest).unwrap();
+        load_manifest(&path).unwrap_err()
+    };
+    let valid = "[[export]]\ntable = \"moves\"\ntarget = \"moves.lua\"\n";
+
+    assert!(error("").ends_with("no export"));
+    assert!(
+        error("[[export]]\ntable = \"moves\"\ntarget = \"moves.lua\"\ncolour = 1\n")
+            .contains("unknown field `colour`")
+    );
+    assert!(error("[[export]]\ntable = \"moves\"\n").contains("missing field `target`"));
+    assert_eq!(
+        error(&format!(
+            "{valid}[[export]]\ntable = \"pokemon-nmes\"\ntarget = \"names.lua\"\n"
+        )),
+        format!(
+            "{}: export 2 (`names.lua`): unknown table `pokemon-nmes`",
+            path.display()
+        )
+    );
+    assert!(error(&format!(
+        "{valid}[[export]]\ntable = \"moves\"\ntarget = \"moves.txt\"\n"
+    ))
+    .ends_with("export 2 (`moves.txt`): no format given and the target has no known extension"));
+    assert!(error(&format!(
+        "{valid}[[export]]\ntable = \"moves\"\ntarget = \"moves.lua\"\nformat = \"json\"\n"
+    ))
+    .ends_with("export 2 (`moves.lua`): same target as export 1"));
+    assert!(
+        error("[[export]]\ntable = \"moves\"\ntarget = \"moves.lua\"\nlanguage = \"it\"\n")
+            .ends_with("export 1 (`moves.lua`): `language` does not apply to moves")
+    );
+    assert!(
+        error("[[export]]\ntable = \"evolutions\"\ntarget = \"e.lua\"\nsource = \"e.csv\"\n")
+            .ends_with("`source` does not apply to evolutions")
+    );
+    assert!(
+        error("[[export]]\ntable = \"moves\"\ntarget = \"moves.lua\"\npriority = \"high\"\n")
+            .ends_with("invalid range `high` for priority")
+    );
+    assert!(
+        error("[[export]]\ntable = \"pokemon-names\"\ntarget = \"n.lua\"\ngeneration = 10\n")
+            .ends_with("unknown generation `10`, expected 1 to 9")
+    );
+    assert!(error(
+        "[[export]]\ntable = \"learnsets\"\ntarget = \"l.lua\"\nlearn_methods = [\"dance\"]\n"
+    )
+    .ends_with("unknown learn method `dance`"));
+}
+
+#[test]
+fn test_run_manifest() {
+    let temp_dir = TempDir::new("test-run-manifest");
+    let dir = temp_dir.path();
+    fs::create_dir_all(dir.join("dump")).unwrap();
+    fs::copy("csv/moves.csv", dir.join("dump/moves.csv")).unwrap();
+    fs::write(
+        dir.join("names.csv"),
+        "id,identifier,species_id,height,weight,base_experience,order,is_default\n\
+         25,pikachu,25,4,60,112,35,1\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("manifest.toml"),
+        r#"input_dir = "dump"
+output_dir = "out"
+
+[[export]]
+table = "moves"
+target = "moves/priority.json"
+priority = "1.."
+damage_class = "physical"
+
+[[export]]
+table = "pokemon-names"
+source = "names.csv"
+target = "names.lua"
+"#,
+    )
+    .unwrap();
+
+    let manifest = load_manifest(&dir.join("manifest.toml")).unwrap();
+    run_manifest(&manifest, &mut Diagnostics::default()).unwrap();
+
+    let moves = fs::read_to_string(dir.join("out/moves/priority.json")).unwrap();
+    // Quick Attack but neither Tackle nor the special Vacuum Wave
+    assert!(moves.contains("  \"1\": [98, 245, 252, 364],\n"));
+    assert!(!moves.contains("410"));
+    let names = fs::read_to_string(dir.join("out/names.lua")).unwrap();
+    assert!(names.contains("\t[25] = \"Pikachu\",\n"));
+    assert!(!names.contains("Bulbasaur"));
+}
 
-    let result = process_type_efficacy(&input);
+#[test]
+fn test_parse_coverage_args() {
+    let args = ["coverage", "fire", "11", "--move", "85", "-i", "dump"];
+
+    let Ok(Command::Coverage(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(config.input_dir, PathBuf::from("dump"));
+    assert_eq!(config.types, vec![Type::Fire, Type::Water]);
+    assert_eq!(config.moves, vec![85]);
+
+    assert!(parse_args(["coverage"].map(String::from)).is_err());
+    assert!(parse_args(["coverage", "shadow"].map(String::from)).is_err());
+    assert!(parse_args(["coverage", "-m", "thunderbolt"].map(String::from)).is_err());
+    // Only the first argument selects the subcommand
+    assert!(parse_args(["moves", "coverage"].map(String::from)).is_err());
+}
 
-    assert_eq!(result, output);
+#[test]
+fn test_run_coverage() {
+    let config = CoverageConfig {
+        input_dir: PathBuf::from("csv"),
+        on_bad_row: OnBadRow::Fail,
+        types: vec![Type::Ice],
+        // Thunderbolt
+        moves: vec![85],
+    };
+
+    let report = run_coverage(&config, &mut Diagnostics::default()).unwrap();
+
+    assert!(report.starts_with("Coverage of Electric, Ice\n"));
+    assert!(report.contains("\nFlying/Water            4x  Electric\n"));
+    assert!(report.contains("\n    Steel/Electric        1/2x\n"));
+
+    let config = CoverageConfig {
+        moves: vec![100_000],
+        ..config
+    };
+    let err = run_coverage(&config, &mut Diagnostics::default()).unwrap_err();
+    assert_eq!(err.to_string(), "no move `100000` in the loaded data");
 }
 
 #[test]
-fn test_moves() {
-    let mut input = String::new();
-    input.push_str("id,identifier,generation_id,type_id,power,pp,accuracy,priority,target_id,damage_class_id,effect_id,effect_chance,contest_type_id,contest_effect_id,super_contest_effect_id\n");
-    input.push_str("1,pound,1,1,40,35,100,0,10,2,1,,5,1,5\n");
-    input.push_str("839,barb-barrage,8,4,60,15,100,0,10,2,3,30,,,\n");
+fn test_parse_team_args() {
+    let args = ["team-report", "-f", "csv", "charizard", "130"];
 
-    let mut output = String::new();
-    output.push_str("[1] = { 1 }, \n");
-    output.push_str("[4] = { 839 }, \n");
+    let Ok(Command::TeamReport(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
 
-    let result = process_moves(&input);
+    assert_eq!(config.format, ReportFormat::Csv);
+    assert_eq!(config.members, vec!["charizard", "130"]);
 
-    assert_eq!(result, output);
+    assert!(parse_args(["team-report"].map(String::from)).is_err());
+    assert!(parse_args(["team-report", "-f", "lua", "mew"].map(String::from)).is_err());
+    let seven = ["team-report", "1", "2", "3", "4", "5", "6", "7"];
+    assert!(parse_args(seven.map(String::from)).is_err());
 }
 
 #[test]
-fn test_move_names() {
-    let mut input = String::new();
-    input.push_str("move_id,local_language_id,name\n");
-    input.push_str("1,1,はたく\n");
-    input.push_str("1,8,Botta\n");
-    input.push_str("825,1,アストラルビット\n");
-    input.push_str("825,8,Schegge Astrali\n");
+fn test_run_team_report() {
+    let config = TeamConfig {
+        input_dir: PathBuf::from("csv"),
+        on_bad_row: OnBadRow::Fail,
+        format: ReportFormat::Csv,
+        members: vec!["Charizard".to_owned(), "130".to_owned()],
+    };
+
+    let report = run_team_report(&config, &mut Diagnostics::default()).unwrap();
+
+    assert!(report.starts_with("attacking_type,charizard,gyarados,weak,"));
+    assert!(report.contains("\nelectric,2,4,2,0,0,true\n"));
+
+    let config = TeamConfig {
+        members: vec!["missingno".to_owned()],
+        ..config
+    };
+    let err = run_team_report(&config, &mut Diagnostics::default()).unwrap_err();
+    assert_eq!(err.to_string(), "no pokemon `missingno` in the loaded data");
+}
 
-    let mut output = String::new();
-    output.push_str("[1] = \"Botta\", \n");
-    output.push_str("[825] = \"Schegge Astrali\", \n");
+#[test]
+fn test_parse_damage_args() {
+    let args = [
+        "damage",
+        "charizard",
+        "53",
+        "3",
+        "--level",
+        "100",
+        "--critical",
+    ];
+
+    let Ok(Command::Damage(config)) = parse_args(args.map(String::from)) else {
+        panic!("arguments should parse");
+    };
+
+    assert_eq!(config.attacker, "charizard");
+    assert_eq!(config.move_id, 53);
+    assert_eq!(config.defender, "3");
+    assert_eq!((config.level, config.defender_level), (100, 100));
+    assert!(config.critical);
+
+    assert!(parse_args(["damage", "charizard", "53"].map(String::from)).is_err());
+    assert!(parse_args(["damage", "charizard", "flamethrower", "3"].map(String::from)).is_err());
+    assert!(parse_args(["damage", "-l", "101", "6", "53", "3"].map(String::from)).is_err());
+}
 
-    let result = process_move_names(&input);
+#[test]
+fn test_run_damage() {
+    let temp_dir = TempDir::new("test-run-damage");
+    let dir = temp_dir.path();
+    for file in ["pokemon.csv", "pokemon_types.csv", "moves.csv"] {
+        fs::copy(Path::new("csv").join(file), dir.join(file)).unwrap();
+    }
+    let mut stats = String::from("pokemon_id,stat_id,base_stat,effort\n");
+    for (pokemon_id, base_stats) in [
+        (3, [80, 82, 83, 100, 100, 80]),
+        (6, [78, 84, 78, 109, 85, 100]),
+    ] {
+        for (stat_id, base_stat) in base_stats.iter().enumerate() {
+            stats += &format!("{pokemon_id},{},{base_stat},0\n", stat_id + 1);
+        }
+    }
+    fs::write(dir.join("pokemon_stats.csv"), stats).unwrap();
+
+    let config = DamageConfig {
+        input_dir: dir.to_owned(),
+        on_bad_row: OnBadRow::Fail,
+        attacker: "charizard".to_owned(),
+        move_id: 53,
+        defender: "venusaur".to_owned(),
+        level: 50,
+        defender_level: 50,
+        critical: false,
+    };
+
+    let report = run_damage(&config, &mut Diagnostics::default()).unwrap();
+    assert_eq!(
+        report,
+        "charizard (level 50) using move 53 on venusaur (level 50)\n\
+         110-132 damage of 155 HP (71.0% - 85.2%), 2x effective\n"
+    );
 
-    assert_eq!(result, output);
+    // Growl
+    let config = DamageConfig {
+        move_id: 45,
+        ..config
+    };
+    let report = run_damage(&config, &mut Diagnostics::default()).unwrap();
+    assert!(report.ends_with("The move deals no direct damage\n"));
+
+    let config = DamageConfig {
+        defender: "pikachu".to_owned(),
+        ..config
+    };
+    let err = run_damage(&config, &mut Diagnostics::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no stats for pokemon `pikachu` in the loaded data"
+    );
 }
@@ -0,0 +1,163 @@
+//! Offensive coverage of a set of attacking types.
+
+use crate::pokedex::Types;
+use crate::type_chart::TypeChart;
+use crate::types::{Effectiveness, Type};
+
+/// The best hit a moveset lands on one defending type combination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matchup {
+    pub defending: Types,
+    /// `None` when the chart has no entry for any attacking type.
+    pub best: Option<Effectiveness>,
+    /// Attacking types reaching `best`, in id order.
+    pub by: Vec<Type>,
+}
+
+impl Matchup {
+    /// Every attacking type is resisted or blocked.
+    pub fn is_wall(&self) -> bool {
+        self.best.is_some_and(|best| best < Effectiveness::NEUTRAL)
+    }
+}
+
+/// Best multiplier of a moveset against every single and dual type combination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// De-duplicated attacking types, in id order.
+    pub attacking: Vec<Type>,
+    /// One entry per combination of [`TypeChart::combinations`].
+    pub matchups: Vec<Matchup>,
+}
+
+impl Coverage {
+    pub fn new(chart: &TypeChart, attacking: &[Type]) -> Coverage {
+        let mut attacking = attacking.to_vec();
+        attacking.sort();
+        attacking.dedup();
+
+        let matchups = chart
+            .combinations()
+            .into_iter()
+            .map(|defending| {
+                let mut best = None;
+                let mut by = Vec::new();
+                for type_ in &attacking {
+                    let Some(effectiveness) = chart.against(*type_, defending) else {
+                        continue;
+                    };
+                    if best.is_none_or(|best| effectiveness > best) {
+                        best = Some(effectiveness);
+                        by.clear();
+                    }
+                    if best == Some(effectiveness) {
+                        by.push(*type_);
+                    }
+                }
+                Matchup {
+                    defending,
+                    best,
+                    by,
+                }
+            })
+            .collect();
+
+        Coverage {
+            attacking,
+            matchups,
+        }
+    }
+
+    /// The matchup against `defending`, whatever the order of its types.
+    pub fn matchup(&self, defending: Types) -> Option<&Matchup> {
+        let swapped = defending.second.map(|second| Types {
+            first: second,
+            second: Some(defending.first),
+        });
+        self.matchups
+            .iter()
+            .find(|matchup| matchup.defending == defending || Some(matchup.defending) == swapped)
+    }
+
+    /// Combinations hit for more than neutral damage.
+    pub fn super_effective(&self) -> impl Iterator<Item = &Matchup> {
+        self.matchups
+            .iter()
+            .filter(|matchup| matchup.best.is_some_and(Effectiveness::is_super_effective))
+    }
+
+    /// Combinations resisting or immune to every attacking type.
+    pub fn walls(&self) -> impl Iterator<Item = &Matchup> {
+        self.matchups.iter().filter(|matchup| matchup.is_wall())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dual(first: Type, second: Type) -> Types {
+        Types {
+            first,
+            second: Some(second),
+        }
+    }
+
+    fn single(first: Type) -> Types {
+        Types {
+            first,
+            second: None,
+        }
+    }
+
+    #[test]
+    fn test_best_multiplier() {
+        let coverage = Coverage::new(
+            TypeChart::standard(),
+            &[Type::Electric, Type::Ice, Type::Ice],
+        );
+
+        assert_eq!(coverage.attacking, [Type::Electric, Type::Ice]);
+
+        let gyarados = coverage.matchup(dual(Type::Water, Type::Flying)).unwrap();
+        assert_eq!(gyarados.best, Some(Effectiveness::QUADRUPLE));
+        assert_eq!(gyarados.by, [Type::Electric]);
+
+        let dragonite = coverage.matchup(dual(Type::Flying, Type::Dragon)).unwrap();
+        assert_eq!(dragonite.best, Some(Effectiveness::QUADRUPLE));
+        assert_eq!(dragonite.by, [Type::Ice]);
+
+        let pidgey = coverage.matchup(dual(Type::Normal, Type::Flying)).unwrap();
+        assert_eq!(pidgey.by, [Type::Electric, Type::Ice]);
+    }
+
+    #[test]
+    fn test_walls() {
+        // Electric plus Ice is only walled by a handful of combinations
+        let coverage = Coverage::new(TypeChart::standard(), &[Type::Electric, Type::Ice]);
+        let walls: Vec<Types> = coverage.walls().map(|matchup| matchup.defending).collect();
+
+        assert!(walls.contains(&dual(Type::Steel, Type::Electric)));
+        assert!(!walls.contains(&single(Type::Ground)));
+        assert!(walls
+            .iter()
+            .all(|types| coverage.matchup(*types).unwrap().is_wall()));
+
+        let coverage = Coverage::new(TypeChart::standard(), &[Type::Normal]);
+        let walls: Vec<Types> = coverage.walls().map(|matchup| matchup.defending).collect();
+        assert!(walls.contains(&single(Type::Ghost)));
+        assert!(walls.contains(&dual(Type::Rock, Type::Steel)));
+        assert_eq!(coverage.super_effective().count(), 0);
+    }
+
+    #[test]
+    fn test_empty_moveset() {
+        let coverage = Coverage::new(TypeChart::standard(), &[]);
+
+        assert!(coverage
+            .matchups
+            .iter()
+            .all(|matchup| matchup.best.is_none()));
+        assert_eq!(coverage.walls().count(), 0);
+    }
+}
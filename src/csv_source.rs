@@ -0,0 +1,204 @@
+use crate::error::{Diagnostics, Error, Location, Result};
+use csv::{Reader, StringRecord};
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// CSV text together with the name used in diagnostics.
+#[derive(Debug, Clone)]
+pub struct CsvSource {
+    name: String,
+    text: String,
+}
+
+/// Name and content hash of a loaded CSV, recorded in generated files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceInfo {
+    pub name: String,
+    /// Lowercase hex SHA-256 of the file contents.
+    pub sha256: String,
+}
+
+/// A deserialized record and where it came from.
+#[derive(Debug, Clone)]
+pub struct Row<T> {
+    pub at: Location,
+    pub value: T,
+}
+
+impl CsvSource {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        CsvSource {
+            name: name.into(),
+            text: text.into(),
+        }
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Ok(CsvSource::new(path.display().to_string(), text))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn info(&self) -> SourceInfo {
+        let digest = Sha256::digest(self.text.as_bytes());
+        SourceInfo {
+            name: self.name.clone(),
+            sha256: digest.iter().map(|byte| format!("{byte:02x}")).collect(),
+        }
+    }
+
+    /// Deserializes every record, routing bad ones through `diagnostics`.
+    pub fn rows<T: DeserializeOwned>(&self, diagnostics: &mut Diagnostics) -> Result<Vec<Row<T>>> {
+        let mut reader = Reader::from_reader(self.text.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|err| self.malformed(&err, None))?
+            .clone();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    diagnostics.report(self.malformed(&err, None))?;
+                    continue;
+                }
+            };
+
+            match record.deserialize::<T>(Some(&headers)) {
+                Ok(value) => rows.push(Row {
+                    at: self.location(&record),
+                    value,
+                }),
+                Err(err) => diagnostics.report(self.malformed(&err, Some(&headers)))?,
+            }
+        }
+
+        Ok(rows)
+    }
+
+    fn location(&self, record: &StringRecord) -> Location {
+        let (line, index) = record
+            .position()
+            .map_or((0, 0), |pos| (pos.line(), pos.record()));
+        Location {
+            file: self.name.clone(),
+            line,
+            // The header is record 0 for the csv crate
+            record: index.saturating_sub(1),
+        }
+    }
+
+    fn malformed(&self, err: &csv::Error, headers: Option<&StringRecord>) -> Error {
+        let (line, record) = err
+            .position()
+            .map_or((0, 0), |pos| (pos.line(), pos.record().saturating_sub(1)));
+        let at = Location {
+            file: self.name.clone(),
+            line,
+            record,
+        };
+
+        match err.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => Error::MalformedRow {
+                at,
+                column: err.field().map(|field| {
+                    headers
+                        .and_then(|headers| headers.get(field as usize))
+                        .map_or_else(|| field.to_string(), str::to_owned)
+                }),
+                message: err.kind().to_string(),
+            },
+            _ => Error::MalformedRow {
+                at,
+                column: None,
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Deserializes the `0`/`1` flags of PokeAPI columns such as `is_default`.
+pub(crate) fn flag<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<bool, D::Error> {
+    match u8::deserialize(deserializer)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => Err(de::Error::custom(format!("expected 0 or 1, found {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OnBadRow;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Move {
+        id: u64,
+        power: Option<u64>,
+    }
+
+    const MOVES: &str = "id,power\n1,40\n2,strong\n3,\n";
+
+    #[test]
+    fn test_malformed_row_position() {
+        let source = CsvSource::new("moves.csv", MOVES);
+
+        let err = source
+            .rows::<Move>(&mut Diagnostics::default())
+            .unwrap_err();
+
+        let Error::MalformedRow { at, column, .. } = &err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(at.file, "moves.csv");
+        assert_eq!(at.line, 3);
+        assert_eq!(at.record, 1);
+        assert_eq!(column.as_deref(), Some("power"));
+        assert!(err
+            .to_string()
+            .starts_with("moves.csv:3: malformed row, column `power`"));
+    }
+
+    #[test]
+    fn test_source_info() {
+        let info = CsvSource::new("empty.csv", "").info();
+
+        assert_eq!(info.name, "empty.csv");
+        assert_eq!(
+            info.sha256,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_skip_bad_rows() {
+        let source = CsvSource::new("moves.csv", MOVES);
+        let mut diagnostics = Diagnostics::new(OnBadRow::SkipAndWarn);
+
+        let rows = source.rows::<Move>(&mut diagnostics).unwrap();
+
+        let ids: Vec<u64> = rows.iter().map(|row| row.value.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(rows[0].value.power, Some(40));
+        assert_eq!(rows[1].at.line, 4);
+        assert_eq!(diagnostics.warnings().len(), 1);
+    }
+}
@@ -0,0 +1,293 @@
+//! Main-series damage formula, as used since Generation V.
+
+use crate::pokedex::{BaseStats, DamageClass, Move, Types};
+use crate::type_chart::TypeChart;
+use crate::types::{Effectiveness, Type};
+
+/// Modifiers in 4096ths, the fixed point the games use.
+const STAB: u64 = 6144;
+const CRITICAL: u64 = 6144;
+/// Lowest and highest random roll in percent.
+const ROLLS: [u64; 2] = [85, 100];
+
+/// A Pokemon with everything the formula reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Combatant {
+    pub base_stats: BaseStats,
+    pub types: Types,
+    pub level: u64,
+    /// Individual value applied to every stat, 0 to 31.
+    pub iv: u64,
+    /// Effort values applied to every stat, 0 to 252.
+    pub ev: u64,
+}
+
+impl Combatant {
+    /// A level-`level` Pokemon with perfect IVs and no EVs.
+    pub fn new(base_stats: BaseStats, types: Types, level: u64) -> Combatant {
+        Combatant {
+            base_stats,
+            types,
+            level,
+            iv: 31,
+            ev: 0,
+        }
+    }
+
+    pub fn max_hp(&self) -> u64 {
+        (2 * self.base_stats.hp + self.iv + self.ev / 4) * self.level / 100 + self.level + 10
+    }
+
+    /// A non-HP stat with a neutral nature.
+    pub fn stat(&self, base: u64) -> u64 {
+        (2 * base + self.iv + self.ev / 4) * self.level / 100 + 5
+    }
+}
+
+/// Damage of one hit from the lowest to the highest random roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageRange {
+    pub min: u64,
+    pub max: u64,
+    pub target_hp: u64,
+    pub effectiveness: Effectiveness,
+}
+
+impl DamageRange {
+    pub fn min_percent(&self) -> f64 {
+        percent(self.min, self.target_hp)
+    }
+
+    pub fn max_percent(&self) -> f64 {
+        percent(self.max, self.target_hp)
+    }
+}
+
+fn percent(damage: u64, hp: u64) -> f64 {
+    if hp == 0 {
+        return 0.0;
+    }
+    damage as f64 * 100.0 / hp as f64
+}
+
+/// Damage `move_` deals from `attacker` to `defender`.
+///
+/// `None` for status moves, moves without a base power or of a type the
+/// chart does not know.
+pub fn calculate(
+    chart: &TypeChart,
+    attacker: &Combatant,
+    defender: &Combatant,
+    move_: &Move,
+    critical: bool,
+) -> Option<DamageRange> {
+    let power = move_.power?;
+    let move_type = Type::from_id(move_.type_id)?;
+    let (attack, defense) = match move_.damage_class()? {
+        DamageClass::Status => return None,
+        DamageClass::Physical => (
+            attacker.stat(attacker.base_stats.attack),
+            defender.stat(defender.base_stats.defense),
+        ),
+        DamageClass::Special => (
+            attacker.stat(attacker.base_stats.special_attack),
+            defender.stat(defender.base_stats.special_defense),
+        ),
+    };
+    let effectiveness = chart.against(move_type, defender.types)?;
+    let stab = attacker.types.iter().any(|type_| type_ == move_type);
+
+    let [min, max] = ROLLS.map(|roll| {
+        let modifiers = Modifiers {
+            critical,
+            roll,
+            stab,
+            effectiveness,
+        };
+        damage(attacker.level, power, attack, defense, modifiers)
+    });
+    Some(DamageRange {
+        min,
+        max,
+        target_hp: defender.max_hp(),
+        effectiveness,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Modifiers {
+    critical: bool,
+    /// Random roll in percent, 85 to 100.
+    roll: u64,
+    stab: bool,
+    effectiveness: Effectiveness,
+}
+
+/// The formula on final stats, applying modifiers in the games' order.
+fn damage(level: u64, power: u64, attack: u64, defense: u64, modifiers: Modifiers) -> u64 {
+    let mut damage = (2 * level / 5 + 2) * power * attack / defense.max(1) / 50 + 2;
+    if modifiers.critical {
+        damage = apply(damage, CRITICAL);
+    }
+    damage = damage * modifiers.roll / 100;
+    if modifiers.stab {
+        damage = apply(damage, STAB);
+    }
+    damage = match modifiers.effectiveness.percent() {
+        0 => return 0,
+        percent => damage * percent / 100,
+    };
+    damage.max(1)
+}
+
+/// Multiplies by `modifier` 4096ths, rounding halves down.
+fn apply(value: u64, modifier: u64) -> u64 {
+    (value * modifier + 2047) / 4096
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifiers(roll: u64, effectiveness: Effectiveness) -> Modifiers {
+        Modifiers {
+            critical: false,
+            roll,
+            stab: true,
+            effectiveness,
+        }
+    }
+
+    fn venusaur() -> Combatant {
+        let base_stats = BaseStats {
+            hp: 80,
+            attack: 82,
+            defense: 83,
+            special_attack: 100,
+            special_defense: 100,
+            speed: 80,
+        };
+        let types = Types {
+            first: Type::Grass,
+            second: Some(Type::Poison),
+        };
+        Combatant::new(base_stats, types, 50)
+    }
+
+    fn charizard() -> Combatant {
+        let base_stats = BaseStats {
+            hp: 78,
+            attack: 84,
+            defense: 78,
+            special_attack: 109,
+            special_defense: 85,
+            speed: 100,
+        };
+        let types = Types {
+            first: Type::Fire,
+            second: Some(Type::Flying),
+        };
+        Combatant::new(base_stats, types, 50)
+    }
+
+    fn flamethrower() -> Move {
+        Move {
+            id: 53,
+            identifier: "flamethrower".to_owned(),
+            generation_id: 1,
+            type_id: Type::Fire.id(),
+            power: Some(90),
+            pp: Some(15),
+            accuracy: Some(100),
+            priority: 0,
+            target_id: 10,
+            damage_class_id: DamageClass::Special as u64,
+            effect_id: Some(5),
+            effect_chance: Some(10),
+            contest_type_id: None,
+            contest_effect_id: None,
+            super_contest_effect_id: None,
+        }
+    }
+
+    #[test]
+    fn test_formula() {
+        // Level 75 Glaceon with 123 Attack using Ice Fang on a Garchomp with 163 Defense
+        let min = damage(75, 65, 123, 163, modifiers(85, Effectiveness::QUADRUPLE));
+        let max = damage(75, 65, 123, 163, modifiers(100, Effectiveness::QUADRUPLE));
+
+        assert_eq!((min, max), (168, 196));
+    }
+
+    #[test]
+    fn test_immune_and_minimum() {
+        assert_eq!(
+            damage(50, 90, 100, 100, modifiers(100, Effectiveness::IMMUNE)),
+            0
+        );
+        assert_eq!(
+            damage(1, 10, 5, 500, modifiers(85, Effectiveness::QUARTER)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let charizard = charizard();
+
+        assert_eq!(charizard.max_hp(), 153);
+        assert_eq!(charizard.stat(charizard.base_stats.special_attack), 129);
+
+        let trained = Combatant {
+            ev: 252,
+            level: 100,
+            ..charizard
+        };
+        assert_eq!(trained.max_hp(), 360);
+        assert_eq!(trained.stat(trained.base_stats.special_attack), 317);
+    }
+
+    #[test]
+    fn test_calculate() {
+        let chart = TypeChart::standard();
+
+        let range = calculate(chart, &charizard(), &venusaur(), &flamethrower(), false).unwrap();
+
+        assert_eq!(range.effectiveness, Effectiveness::DOUBLE);
+        assert_eq!(range.target_hp, 155);
+        assert_eq!((range.min, range.max), (110, 132));
+        assert!(range.min_percent() > 70.9 && range.max_percent() < 85.2);
+
+        let critical = calculate(chart, &charizard(), &venusaur(), &flamethrower(), true).unwrap();
+        assert!(critical.min > range.min && critical.max > range.max);
+
+        // No STAB and resisted
+        let reversed = Move {
+            type_id: Type::Grass.id(),
+            ..flamethrower()
+        };
+        let range = calculate(chart, &venusaur(), &charizard(), &reversed, false).unwrap();
+        assert_eq!(range.effectiveness, Effectiveness::QUARTER);
+        assert!(range.max < 20);
+    }
+
+    #[test]
+    fn test_calculate_status() {
+        let growl = Move {
+            power: None,
+            damage_class_id: DamageClass::Status as u64,
+            ..flamethrower()
+        };
+
+        assert_eq!(
+            calculate(
+                TypeChart::standard(),
+                &charizard(),
+                &venusaur(),
+                &growl,
+                false
+            ),
+            None
+        );
+    }
+}
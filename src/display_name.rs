@@ -0,0 +1,280 @@
+//! Official display names of PokeAPI Pokemon identifiers, such as `Mr. Mime`
+//! for `mr-mime` or `Enamorus (Incarnate Forme)` for `enamorus-incarnate`.
+
+/// Species whose name is not the capitalized identifier, including every
+/// species with a `-` in its identifier so forms are split after it.
+const SPECIES: [(&str, &str); 42] = [
+    ("nidoran-f", "Nidoran♀"),
+    ("nidoran-m", "Nidoran♂"),
+    ("farfetchd", "Farfetch’d"),
+    ("mr-mime", "Mr. Mime"),
+    ("ho-oh", "Ho-Oh"),
+    ("mime-jr", "Mime Jr."),
+    ("porygon-z", "Porygon-Z"),
+    ("flabebe", "Flabébé"),
+    ("type-null", "Type: Null"),
+    ("jangmo-o", "Jangmo-o"),
+    ("hakamo-o", "Hakamo-o"),
+    ("kommo-o", "Kommo-o"),
+    ("tapu-koko", "Tapu Koko"),
+    ("tapu-lele", "Tapu Lele"),
+    ("tapu-bulu", "Tapu Bulu"),
+    ("tapu-fini", "Tapu Fini"),
+    ("sirfetchd", "Sirfetch’d"),
+    ("mr-rime", "Mr. Rime"),
+    ("great-tusk", "Great Tusk"),
+    ("scream-tail", "Scream Tail"),
+    ("brute-bonnet", "Brute Bonnet"),
+    ("flutter-mane", "Flutter Mane"),
+    ("slither-wing", "Slither Wing"),
+    ("sandy-shocks", "Sandy Shocks"),
+    ("iron-treads", "Iron Treads"),
+    ("iron-bundle", "Iron Bundle"),
+    ("iron-hands", "Iron Hands"),
+    ("iron-jugulis", "Iron Jugulis"),
+    ("iron-moth", "Iron Moth"),
+    ("iron-thorns", "Iron Thorns"),
+    ("wo-chien", "Wo-Chien"),
+    ("chien-pao", "Chien-Pao"),
+    ("ting-lu", "Ting-Lu"),
+    ("chi-yu", "Chi-Yu"),
+    ("roaring-moon", "Roaring Moon"),
+    ("iron-valiant", "Iron Valiant"),
+    ("walking-wake", "Walking Wake"),
+    ("iron-leaves", "Iron Leaves"),
+    ("gouging-fire", "Gouging Fire"),
+    ("raging-bolt", "Raging Bolt"),
+    ("iron-boulder", "Iron Boulder"),
+    ("iron-crown", "Iron Crown"),
+];
+
+/// Forms the rules below get wrong, by full identifier.
+const FORMS: [(&str, &str); 17] = [
+    ("kyurem-black", "Black Kyurem"),
+    ("kyurem-white", "White Kyurem"),
+    ("necrozma-dusk", "Dusk Mane Necrozma"),
+    ("necrozma-dawn", "Dawn Wings Necrozma"),
+    ("necrozma-ultra", "Ultra Necrozma"),
+    ("calyrex-ice", "Ice Rider Calyrex"),
+    ("calyrex-shadow", "Shadow Rider Calyrex"),
+    ("hoopa-unbound", "Hoopa Unbound"),
+    ("rotom-heat", "Heat Rotom"),
+    ("rotom-wash", "Wash Rotom"),
+    ("rotom-frost", "Frost Rotom"),
+    ("rotom-fan", "Fan Rotom"),
+    ("rotom-mow", "Mow Rotom"),
+    ("zygarde-10", "Zygarde (10% Forme)"),
+    ("zygarde-50", "Zygarde (50% Forme)"),
+    ("pikachu-starter", "Partner Pikachu"),
+    ("eevee-starter", "Partner Eevee"),
+];
+
+/// Form words written before the species name, e.g. `Mega Charizard X`.
+const PREFIXES: [(&str, &str); 8] = [
+    ("mega", "Mega"),
+    ("primal", "Primal"),
+    ("gmax", "Gigantamax"),
+    ("totem", "Totem"),
+    ("alola", "Alolan"),
+    ("galar", "Galarian"),
+    ("hisui", "Hisuian"),
+    ("paldea", "Paldean"),
+];
+
+/// What the games call the forms of a species, `Form` otherwise.
+const FORM_NOUNS: [(&str, &str); 17] = [
+    ("deoxys", "Forme"),
+    ("giratina", "Forme"),
+    ("shaymin", "Forme"),
+    ("tornadus", "Forme"),
+    ("thundurus", "Forme"),
+    ("landorus", "Forme"),
+    ("enamorus", "Forme"),
+    ("meloetta", "Forme"),
+    ("aegislash", "Forme"),
+    ("zygarde", "Forme"),
+    ("burmy", "Cloak"),
+    ("wormadam", "Cloak"),
+    ("darmanitan", "Mode"),
+    ("oricorio", "Style"),
+    ("urshifu", "Style"),
+    ("pumpkaboo", "Size"),
+    ("gourgeist", "Size"),
+];
+
+/// Form words naming themselves, e.g. `Pikachu (Alola Cap)`.
+const SELF_NAMED: [&str; 3] = ["cap", "male", "female"];
+
+fn lookup<'a>(table: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(identifier, _)| *identifier == key)
+        .map(|(_, name)| *name)
+}
+
+/// `word` with its first character uppercased, whatever its length in bytes.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Splits an identifier into the species and form parts, the longest
+/// species of [`SPECIES`] winning over the first word.
+fn split(identifier: &str) -> (&str, &str) {
+    let known = SPECIES
+        .iter()
+        .map(|(species, _)| *species)
+        .filter(|species| {
+            identifier
+                .strip_prefix(species)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        })
+        .max_by_key(|species| species.len());
+    let species = known.unwrap_or_else(|| identifier.split('-').next().unwrap_or_default());
+    let form = identifier[species.len()..].trim_start_matches('-');
+    (species, form)
+}
+
+/// The name the games display for a PokeAPI Pokemon identifier.
+///
+/// Species come from an override table or are capitalized; form words
+/// become a prefix such as `Alolan` or a suffix such as `(Zen Mode)`.
+pub fn display_name(identifier: &str) -> String {
+    if let Some(name) = lookup(&FORMS, identifier) {
+        return name.to_owned();
+    }
+    let (species, form) = split(identifier);
+    let species_name = lookup(&SPECIES, species).map_or_else(|| capitalize(species), str::to_owned);
+
+    let mut prefixes: Vec<&str> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let is_cap = form.ends_with("-cap");
+    for word in form.split('-').filter(|word| !word.is_empty()) {
+        match lookup(&PREFIXES, word) {
+            // Regional caps are Pikachu forms, not regional variants
+            Some(prefix) if !is_cap => prefixes.push(prefix),
+            _ => words.push(capitalize(word)),
+        }
+    }
+
+    let mut name = prefixes.join(" ");
+    if !name.is_empty() {
+        name.push(' ');
+    }
+    name.push_str(&species_name);
+    // Mega evolutions end with their letter, e.g. `charizard-mega-x`
+    if prefixes.contains(&"Mega") && words.len() == 1 && words[0].chars().count() == 1 {
+        name.push(' ');
+        name.push_str(&words[0]);
+        return name;
+    }
+    if !words.is_empty() {
+        let last = form.rsplit('-').next().unwrap_or_default();
+        let noun = if SELF_NAMED.contains(&last) {
+            None
+        } else {
+            Some(lookup(&FORM_NOUNS, species).unwrap_or("Form"))
+        };
+        name.push_str(" (");
+        name.push_str(&words.join(" "));
+        if let Some(noun) = noun {
+            name.push(' ');
+            name.push_str(noun);
+        }
+        name.push(')');
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_source::CsvSource;
+    use crate::error::Diagnostics;
+    use crate::pokedex::Pokedex;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_species() {
+        assert_eq!(display_name("bulbasaur"), "Bulbasaur");
+        assert_eq!(display_name("mr-mime"), "Mr. Mime");
+        assert_eq!(display_name("ho-oh"), "Ho-Oh");
+        assert_eq!(display_name("nidoran-f"), "Nidoran♀");
+        assert_eq!(display_name("nidoran-m"), "Nidoran♂");
+        assert_eq!(display_name("farfetchd"), "Farfetch’d");
+        assert_eq!(display_name("type-null"), "Type: Null");
+        assert_eq!(display_name("tapu-koko"), "Tapu Koko");
+        assert_eq!(display_name("jangmo-o"), "Jangmo-o");
+        assert_eq!(display_name("chi-yu"), "Chi-Yu");
+        assert_eq!(display_name("great-tusk"), "Great Tusk");
+    }
+
+    #[test]
+    fn test_forms() {
+        assert_eq!(
+            display_name("enamorus-incarnate"),
+            "Enamorus (Incarnate Forme)"
+        );
+        assert_eq!(display_name("deoxys-attack"), "Deoxys (Attack Forme)");
+        assert_eq!(
+            display_name("darmanitan-galar-zen"),
+            "Galarian Darmanitan (Zen Mode)"
+        );
+        assert_eq!(display_name("mr-mime-galar"), "Galarian Mr. Mime");
+        assert_eq!(display_name("raichu-alola"), "Alolan Raichu");
+        assert_eq!(display_name("charizard-mega-x"), "Mega Charizard X");
+        assert_eq!(display_name("venusaur-mega"), "Mega Venusaur");
+        assert_eq!(display_name("charizard-gmax"), "Gigantamax Charizard");
+        assert_eq!(
+            display_name("urshifu-rapid-strike-gmax"),
+            "Gigantamax Urshifu (Rapid Strike Style)"
+        );
+        assert_eq!(display_name("pikachu-alola-cap"), "Pikachu (Alola Cap)");
+        assert_eq!(display_name("meowstic-female"), "Meowstic (Female)");
+        assert_eq!(display_name("lycanroc-midday"), "Lycanroc (Midday Form)");
+        assert_eq!(display_name("kyurem-black"), "Black Kyurem");
+        assert_eq!(display_name("porygon-z"), "Porygon-Z");
+        assert_eq!(display_name("kommo-o-totem"), "Totem Kommo-o");
+        assert_eq!(display_name("gourgeist-super"), "Gourgeist (Super Size)");
+    }
+
+    #[test]
+    fn test_degenerate_identifiers() {
+        assert_eq!(display_name(""), "");
+        assert_eq!(display_name("-"), "");
+        assert_eq!(display_name("mew-"), "Mew");
+        assert_eq!(display_name("éevee"), "Éevee");
+        assert_eq!(display_name("ピカチュウ-x"), "ピカチュウ (X Form)");
+    }
+
+    #[test]
+    fn test_bundled_pokemon() {
+        let mut dex = Pokedex::new();
+        dex.load_pokemon(
+            &CsvSource::new("pokemon.csv", include_str!("../csv/pokemon.csv")),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        let mut names = HashSet::new();
+        for pokemon in dex.pokemon() {
+            let name = display_name(&pokemon.name);
+            // Only default forms are bundled
+            assert!(
+                !name.contains('('),
+                "{} is not a species: {name}",
+                pokemon.name
+            );
+            assert!(
+                name.starts_with(char::is_uppercase),
+                "{} should be capitalized: {name}",
+                pokemon.name
+            );
+            assert!(names.insert(name), "{} has a duplicate name", pokemon.name);
+        }
+        assert_eq!(names.len(), 905);
+    }
+}
@@ -0,0 +1,373 @@
+use super::{Emitter, Shape, Table, Value};
+use std::collections::BTreeMap;
+
+/// A C header with `static const` arrays and `#define` counts.
+///
+/// Every entry is flattened into rows of a struct array: maps and lists of
+/// records add one row per element, while lists of scalars become an
+/// offset/count pair into a separate `<table>_<column>_items` array.
+pub struct C;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Int,
+    Str,
+    Span(Scalar),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    Int,
+    Str,
+}
+
+struct Column {
+    name: String,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Cell {
+    Int(i64),
+    Str(String),
+    Span(usize, usize),
+}
+
+impl Emitter for C {
+    fn extension(&self) -> &'static str {
+        "h"
+    }
+
+    fn emit(&self, table: &Table) -> String {
+        let shape = table.shape();
+        let columns = columns(&shape, "");
+
+        let mut items: BTreeMap<String, Vec<Cell>> = BTreeMap::new();
+        let rows: Vec<Vec<Cell>> = table
+            .entries
+            .iter()
+            .flat_map(|(key, value)| {
+                rows(value, &shape, "", &mut items)
+                    .into_iter()
+                    .map(|row| {
+                        let mut cells = vec![Cell::Int(*key as i64)];
+                        cells.extend(row);
+                        cells
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let name = identifier(&table.name);
+        let upper = name.to_uppercase();
+
+        let mut out = String::new();
+        out.push_str(&format!("#ifndef {upper}_H\n#define {upper}_H\n\n"));
+        out.push_str("#include <stdint.h>\n\n");
+        out.push_str(&format!("#define {upper}_COUNT {}\n", rows.len()));
+        for column in &columns {
+            if let Kind::Span(_) = column.kind {
+                out.push_str(&format!(
+                    "#define {upper}_{}_ITEMS_COUNT {}\n",
+                    column.name.to_uppercase(),
+                    items.get(&column.name).map_or(0, Vec::len)
+                ));
+            }
+        }
+
+        for column in &columns {
+            let Kind::Span(scalar) = column.kind else {
+                continue;
+            };
+            let values = items.get(&column.name).map_or(&[][..], Vec::as_slice);
+            let size = array_size(
+                format!("{upper}_{}_ITEMS_COUNT", column.name.to_uppercase()),
+                values.len(),
+            );
+            out.push_str(&format!(
+                "\nstatic {} {name}_{}_items[{size}] = {{\n",
+                scalar_type(scalar),
+                column.name
+            ));
+            for chunk in values.chunks(16) {
+                let line: Vec<String> = chunk.iter().map(cell_to_c).collect();
+                out.push_str(&format!("    {},\n", line.join(", ")));
+            }
+            if values.is_empty() {
+                out.push_str("    0,\n");
+            }
+            out.push_str("};\n");
+        }
+
+        out.push_str("\nstatic const struct {\n    uint32_t id;\n");
+        for column in &columns {
+            match column.kind {
+                Kind::Int => out.push_str(&format!("    int32_t {};\n", column.name)),
+                Kind::Str => out.push_str(&format!("    const char *{};\n", column.name)),
+                Kind::Span(_) => out.push_str(&format!(
+                    "    uint32_t {0}_offset;\n    uint32_t {0}_count;\n",
+                    column.name
+                )),
+            }
+        }
+        let size = array_size(format!("{upper}_COUNT"), rows.len());
+        out.push_str(&format!("}} {name}[{size}] = {{\n"));
+        for row in &rows {
+            let cells: Vec<String> = row.iter().map(cell_to_c).collect();
+            out.push_str(&format!("    {{ {} }},\n", cells.join(", ")));
+        }
+        if rows.is_empty() {
+            out.push_str("    { 0 },\n");
+        }
+        out.push_str("};\n\n");
+        out.push_str(&format!("#endif /* {upper}_H */\n"));
+        out
+    }
+}
+
+/// The size of an array of `count` elements, named by the `define` of the
+/// count. C has no zero-length arrays, so an empty one gets a single zeroed
+/// element that the count leaves out.
+fn array_size(define: String, count: usize) -> String {
+    if count == 0 {
+        "1".to_owned()
+    } else {
+        define
+    }
+}
+
+fn scalar_type(scalar: Scalar) -> &'static str {
+    match scalar {
+        Scalar::Int => "const int32_t",
+        Scalar::Str => "const char *const",
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        identifier(name)
+    } else {
+        format!("{prefix}_{}", identifier(name))
+    }
+}
+
+/// Replaces everything C does not accept in an identifier with `_`.
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn column_name(prefix: &str) -> String {
+    if prefix.is_empty() {
+        "value".to_owned()
+    } else {
+        prefix.to_owned()
+    }
+}
+
+fn columns(shape: &Shape, prefix: &str) -> Vec<Column> {
+    match shape {
+        Shape::Unknown | Shape::Int => vec![Column {
+            name: column_name(prefix),
+            kind: Kind::Int,
+        }],
+        Shape::Str => vec![Column {
+            name: column_name(prefix),
+            kind: Kind::Str,
+        }],
+        Shape::List(item) if item.is_scalar() => vec![Column {
+            name: column_name(prefix),
+            kind: Kind::Span(if **item == Shape::Str {
+                Scalar::Str
+            } else {
+                Scalar::Int
+            }),
+        }],
+        Shape::List(item) => columns(item, prefix),
+        Shape::Map(value) => {
+            let mut columns = vec![Column {
+                name: join(prefix, "key"),
+                kind: Kind::Int,
+            }];
+            columns.extend(self::columns(value, prefix));
+            columns
+        }
+        Shape::Record(fields) => fields
+            .iter()
+            .flat_map(|(name, shape)| columns(shape, &join(prefix, name)))
+            .collect(),
+    }
+}
+
+/// Cells used when a value is missing or does not match the table shape.
+fn empty_row(shape: &Shape, prefix: &str) -> Vec<Cell> {
+    columns(shape, prefix)
+        .into_iter()
+        .map(|column| match column.kind {
+            Kind::Int => Cell::Int(0),
+            Kind::Str => Cell::Str(String::new()),
+            Kind::Span(_) => Cell::Span(0, 0),
+        })
+        .collect()
+}
+
+fn rows(
+    value: &Value,
+    shape: &Shape,
+    prefix: &str,
+    items: &mut BTreeMap<String, Vec<Cell>>,
+) -> Vec<Vec<Cell>> {
+    match (shape, value) {
+        (Shape::Unknown | Shape::Int, Value::Int(value)) => vec![vec![Cell::Int(*value)]],
+        (Shape::Str, Value::Str(value)) => vec![vec![Cell::Str(value.clone())]],
+        (Shape::List(item), Value::List(values)) if item.is_scalar() => {
+            let column = items.entry(column_name(prefix)).or_default();
+            let offset = column.len();
+            column.extend(values.iter().filter_map(|value| match value {
+                Value::Int(value) => Some(Cell::Int(*value)),
+                Value::Str(value) => Some(Cell::Str(value.clone())),
+                _ => None,
+            }));
+            vec![vec![Cell::Span(offset, column.len() - offset)]]
+        }
+        (Shape::List(item), Value::List(values)) => values
+            .iter()
+            .flat_map(|value| rows(value, item, prefix, items))
+            .collect(),
+        (Shape::Map(item), Value::Map(entries)) => entries
+            .iter()
+            .flat_map(|(key, value)| {
+                rows(value, item, prefix, items)
+                    .into_iter()
+                    .map(|row| {
+                        let mut cells = vec![Cell::Int(*key as i64)];
+                        cells.extend(row);
+                        cells
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        (Shape::Record(fields), Value::Record(values)) => {
+            fields.iter().fold(vec![Vec::new()], |acc, (name, shape)| {
+                let prefix = join(prefix, name);
+                let field_rows = match values.iter().find(|(field, _)| field == name) {
+                    Some((_, value)) => rows(value, shape, &prefix, items),
+                    None => vec![empty_row(shape, &prefix)],
+                };
+                // Each row of a field is combined with every row so far
+                acc.iter()
+                    .flat_map(|row| {
+                        field_rows.iter().map(move |field_row| {
+                            let mut cells = row.clone();
+                            cells.extend(field_row.iter().cloned());
+                            cells
+                        })
+                    })
+                    .collect()
+            })
+        }
+        (shape, _) => vec![empty_row(shape, prefix)],
+    }
+}
+
+fn cell_to_c(cell: &Cell) -> String {
+    match cell {
+        Cell::Int(value) => value.to_string(),
+        Cell::Str(value) => escape(value),
+        Cell::Span(offset, count) => format!("{offset}, {count}"),
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // `??` could start a trigraph in older compilers
+            '?' => out.push_str("\\?"),
+            c if c.is_control() => out.push_str(&format!("\\{:03o}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c_strings() {
+        let mut table = Table::new("pokemon_names");
+        table.insert(1, Value::Str("Bulbasaur".to_owned()));
+        table.insert(122, Value::Str("Mr. \"Mime\"".to_owned()));
+
+        let mut output = String::new();
+        output.push_str("#ifndef POKEMON_NAMES_H\n#define POKEMON_NAMES_H\n\n");
+        output.push_str("#include <stdint.h>\n\n");
+        output.push_str("#define POKEMON_NAMES_COUNT 2\n");
+        output.push_str("\nstatic const struct {\n    uint32_t id;\n    const char *value;\n");
+        output.push_str("} pokemon_names[POKEMON_NAMES_COUNT] = {\n");
+        output.push_str("    { 1, \"Bulbasaur\" },\n");
+        output.push_str("    { 122, \"Mr. \\\"Mime\\\"\" },\n");
+        output.push_str("};\n\n#endif /* POKEMON_NAMES_H */\n");
+
+        assert_eq!(C.emit(&table), output);
+    }
+
+    #[test]
+    fn test_c_lists() {
+        let mut table = Table::new("pokemon_weaknesses");
+        table.insert(1, Value::List(vec![Value::Int(3), Value::Int(10)]));
+        table.insert(4, Value::List(vec![]));
+        table.insert(7, Value::List(vec![Value::Int(12)]));
+
+        let output = C.emit(&table);
+
+        assert!(output.contains("#define POKEMON_WEAKNESSES_VALUE_ITEMS_COUNT 3\n"));
+        assert!(output.contains(
+            "static const int32_t pokemon_weaknesses_value_items[POKEMON_WEAKNESSES_VALUE_ITEMS_COUNT] = {\n    3, 10, 12,\n};\n"
+        ));
+        assert!(output.contains("    uint32_t value_offset;\n    uint32_t value_count;\n"));
+        assert!(output.contains("    { 1, 0, 2 },\n    { 4, 2, 0 },\n    { 7, 2, 1 },\n"));
+    }
+
+    #[test]
+    fn test_c_maps() {
+        let mut table = Table::new("type_efficacy");
+        table.insert(
+            2,
+            Value::Map([(1, Value::Int(200)), (8, Value::Int(0))].into()),
+        );
+
+        let output = C.emit(&table);
+
+        assert!(output.contains("#define TYPE_EFFICACY_COUNT 2\n"));
+        assert!(output.contains("    uint32_t id;\n    int32_t key;\n    int32_t value;\n"));
+        assert!(output.contains("    { 2, 1, 200 },\n    { 2, 8, 0 },\n"));
+    }
+
+    #[test]
+    fn test_c_empty() {
+        let table = Table::new("pokemon_weaknesses");
+        let mut lists = Table::new("pokemon_weaknesses");
+        lists.insert(1, Value::List(vec![]));
+
+        let output = C.emit(&table);
+        let empty_lists = C.emit(&lists);
+
+        assert!(output.contains("#define POKEMON_WEAKNESSES_COUNT 0\n"));
+        assert!(output.contains("} pokemon_weaknesses[1] = {\n    { 0 },\n};\n"));
+        assert!(empty_lists.contains("#define POKEMON_WEAKNESSES_VALUE_ITEMS_COUNT 0\n"));
+        assert!(empty_lists
+            .contains("static const int32_t pokemon_weaknesses_value_items[1] = {\n    0,\n};\n"));
+        assert!(empty_lists.contains("    { 1, 0, 0 },\n"));
+    }
+}
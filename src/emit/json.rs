@@ -0,0 +1,143 @@
+use super::{Emitter, Table, Value};
+
+/// A JSON object keyed by id.
+pub struct Json;
+
+impl Emitter for Json {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn emit(&self, table: &Table) -> String {
+        let mut out = String::new();
+        write_object(
+            &mut out,
+            table
+                .entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value)),
+            0,
+        );
+        out.push('\n');
+        out
+    }
+}
+
+/// Quotes and escapes `value` as a JSON string.
+pub(crate) fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn write_object<'a>(
+    out: &mut String,
+    fields: impl ExactSizeIterator<Item = (String, &'a Value)>,
+    depth: usize,
+) {
+    if fields.len() == 0 {
+        out.push_str("{}");
+        return;
+    }
+
+    let indent = "  ".repeat(depth + 1);
+    out.push_str("{\n");
+    for (index, (key, value)) in fields.enumerate() {
+        if index > 0 {
+            out.push_str(",\n");
+        }
+        out.push_str(&format!("{indent}{}: ", escape(&key)));
+        write_value(out, value, depth + 1);
+    }
+    out.push('\n');
+    out.push_str(&"  ".repeat(depth));
+    out.push('}');
+}
+
+fn write_value(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Int(value) => out.push_str(&value.to_string()),
+        Value::Str(value) => out.push_str(&escape(value)),
+        // Lists of scalars stay on one line to keep the files readable
+        Value::List(items)
+            if items
+                .iter()
+                .all(|item| matches!(item, Value::Int(_) | Value::Str(_))) =>
+        {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_value(out, item, depth);
+            }
+            out.push(']');
+        }
+        Value::List(items) => {
+            let indent = "  ".repeat(depth + 1);
+            out.push_str("[\n");
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(",\n");
+                }
+                out.push_str(&indent);
+                write_value(out, item, depth + 1);
+            }
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+            out.push(']');
+        }
+        Value::Map(entries) => write_object(
+            out,
+            entries.iter().map(|(key, value)| (key.to_string(), value)),
+            depth,
+        ),
+        Value::Record(fields) => write_object(
+            out,
+            fields.iter().map(|(name, value)| (name.clone(), value)),
+            depth,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let mut table = Table::new("type_efficacy");
+        table.insert(
+            2,
+            Value::Map([(1, Value::Int(200)), (8, Value::Int(0))].into()),
+        );
+        table.insert(10, Value::List(vec![Value::Int(3), Value::Int(4)]));
+        table.insert(11, Value::Str("Mr. \"Mime\"\n".to_owned()));
+
+        let mut output = String::new();
+        output.push_str("{\n");
+        output.push_str("  \"2\": {\n    \"1\": 200,\n    \"8\": 0\n  },\n");
+        output.push_str("  \"10\": [3, 4],\n");
+        output.push_str("  \"11\": \"Mr. \\\"Mime\\\"\\n\"\n");
+        output.push_str("}\n");
+
+        assert_eq!(Json.emit(&table), output);
+    }
+
+    #[test]
+    fn test_json_empty() {
+        assert_eq!(Json.emit(&Table::new("moves")), "{}\n");
+    }
+}
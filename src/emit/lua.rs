@@ -0,0 +1,596 @@
+use super::{Emitter, Table, Value};
+use crate::csv_source::SourceInfo;
+use std::collections::BTreeMap;
+
+/// A self-contained Lua module returning the table.
+pub struct Lua;
+
+const KEYWORDS: [&str; 22] = [
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+impl Emitter for Lua {
+    fn extension(&self) -> &'static str {
+        "lua"
+    }
+
+    fn emit(&self, table: &Table) -> String {
+        let name = identifier(&table.name);
+
+        let mut out = header(&table.sources);
+        out.push_str(&format!(
+            "local {name} = {}\n\nreturn {name}\n",
+            entries_to_lua(&table.entries, 0)
+        ));
+        out
+    }
+}
+
+impl Lua {
+    /// A single module named `name` holding every table as a field.
+    pub fn emit_combined(&self, name: &str, tables: &[Table]) -> String {
+        let name = identifier(name);
+
+        let mut sources: Vec<SourceInfo> = Vec::new();
+        for source in tables.iter().flat_map(|table| &table.sources) {
+            if !sources.contains(source) {
+                sources.push(source.clone());
+            }
+        }
+
+        let mut out = header(&sources);
+        out.push_str(&format!("local {name} = {{}}\n\n"));
+        for table in tables {
+            out.push_str(&format!(
+                "{name}.{} = {}\n\n",
+                identifier(&table.name),
+                entries_to_lua(&table.entries, 0)
+            ));
+        }
+        out.push_str(&format!("return {name}\n"));
+        out
+    }
+}
+
+fn header(sources: &[SourceInfo]) -> String {
+    let mut out = String::from("-- Generated from PokeAPI CSV data, do not edit by hand.\n");
+    for source in sources {
+        out.push_str(&format!(
+            "-- Source: {} sha256:{}\n",
+            source.name, source.sha256
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+/// Replaces everything Lua does not accept in a name with `_`.
+fn identifier(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || KEYWORDS.contains(&name.as_str()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&name)
+}
+
+/// Quotes `value` as a Lua string literal; UTF-8 is kept as is.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // Always three digits so a following digit is not swallowed
+            c if c.is_ascii_control() => out.push_str(&format!("\\{:03}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn entries_to_lua(entries: &BTreeMap<u64, Value>, depth: usize) -> String {
+    if entries.is_empty() {
+        return "{}".to_owned();
+    }
+
+    let indent = "\t".repeat(depth + 1);
+    let entries = entries.iter().fold(String::new(), |mut acc, (key, value)| {
+        let value = value_to_lua(value, depth + 1);
+        acc.push_str(&format!("{indent}[{key}] = {value},\n"));
+        acc
+    });
+    format!("{{\n{entries}{}}}", "\t".repeat(depth))
+}
+
+fn value_to_lua(value: &Value, depth: usize) -> String {
+    match value {
+        Value::Int(value) => value.to_string(),
+        Value::Str(value) => escape(value),
+        Value::List(items) if items.is_empty() => "{}".to_owned(),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| value_to_lua(item, depth)).collect();
+            format!("{{ {} }}", items.join(", "))
+        }
+        Value::Map(entries) => entries_to_lua(entries, depth),
+        Value::Record(fields) if fields.is_empty() => "{}".to_owned(),
+        Value::Record(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, value)| {
+                    let value = value_to_lua(value, depth);
+                    if is_identifier(name) {
+                        format!("{name} = {value}")
+                    } else {
+                        format!("[{}] = {value}", escape(name))
+                    }
+                })
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_source::CsvSource;
+    use crate::display_name::display_name;
+    use crate::error::{Diagnostics, Result};
+    use crate::pokedex::{MoveFilter, Pokedex};
+    use crate::tables::{self, FormLayout};
+
+    type Loader = fn(&mut Pokedex, &CsvSource, &mut Diagnostics) -> Result<()>;
+
+    /// Wraps entry lines the way every generated module does.
+    fn module(name: &str, entries: &str) -> String {
+        format!("local {name} = {{\n{entries}}}\n\nreturn {name}\n")
+    }
+
+    /// The module without its generated header.
+    fn body(output: &str) -> &str {
+        let start = output
+            .find("\nlocal ")
+            .expect("module should declare a local");
+        &output[start + 1..]
+    }
+
+    fn load(loader: Loader, input: &str) -> Pokedex {
+        let mut dex = Pokedex::new();
+        loader(
+            &mut dex,
+            &CsvSource::new("test.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+        dex
+    }
+
+    #[test]
+    fn test_pokemon_names() {
+        let mut input = String::new();
+        input.push_str("id,identifier,species_id,height,weight,base_experience,order,is_default\n");
+        input.push_str("1,bulbasaur,1,7,69,64,1,1\n");
+        input.push_str("905,enamorus-incarnate,905,16,480,,,1\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = \"Bulbasaur\",\n");
+        output.push_str("\t[905] = \"Enamorus (Incarnate Forme)\",\n");
+
+        let dex = load(Pokedex::load_pokemon, &input);
+        let result = Lua.emit(&tables::pokemon_names(&dex));
+
+        assert_eq!(body(&result), module("pokemon_names", &output));
+    }
+
+    #[test]
+    fn test_pokemon_types() {
+        let mut input = String::new();
+        input.push_str("pokemon_id,type_id,slot\n");
+        input.push_str("1,12,1\n");
+        input.push_str("1,4,2\n");
+        input.push_str("897,8,1\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = { 12, 4 },\n");
+        output.push_str("\t[897] = { 8, 0 },\n");
+
+        let dex = load(Pokedex::load_pokemon_types, &input);
+        let result = Lua.emit(&tables::pokemon_types(&dex));
+
+        assert_eq!(body(&result), module("pokemon_types", &output));
+    }
+
+    #[test]
+    fn test_pokemon_weaknesses() {
+        let mut input = String::new();
+        input.push_str("pokemon_id,type_id,slot\n");
+        input.push_str("1,12,1\n");
+        input.push_str("1,4,2\n");
+        input.push_str("895,16,1\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = { 3, 10, 14, 15 },\n");
+        output.push_str("\t[895] = { 15, 16, 18 },\n");
+
+        let dex = load(Pokedex::load_pokemon_types, &input);
+        let result = Lua.emit(&tables::pokemon_weaknesses(&dex));
+
+        assert_eq!(body(&result), module("pokemon_weaknesses", &output));
+    }
+
+    #[test]
+    fn test_type_efficacy() {
+        let mut input = String::new();
+        input.push_str("damage_type_id,target_type_id,damage_factor\n");
+        input.push_str("1,1,100\n");
+        input.push_str("18,17,200\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = {\n\t\t[1] = 100,\n\t},\n");
+        output.push_str("\t[18] = {\n\t\t[17] = 200,\n\t},\n");
+
+        let dex = load(Pokedex::load_type_efficacy, &input);
+        let result = Lua.emit(&tables::type_efficacy(&dex));
+
+        assert_eq!(body(&result), module("type_efficacy", &output));
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut input = String::new();
+        input.push_str("id,identifier,generation_id,type_id,power,pp,accuracy,priority,target_id,damage_class_id,effect_id,effect_chance,contest_type_id,contest_effect_id,super_contest_effect_id\n");
+        input.push_str("1,pound,1,1,40,35,100,0,10,2,1,,5,1,5\n");
+        input.push_str("839,barb-barrage,8,4,60,15,100,0,10,2,3,30,,,\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = { 1 },\n");
+        output.push_str("\t[4] = { 839 },\n");
+
+        let dex = load(Pokedex::load_moves, &input);
+        let result = Lua.emit(&tables::moves(&dex, &MoveFilter::damaging()));
+
+        assert_eq!(body(&result), module("moves", &output));
+    }
+
+    #[test]
+    fn test_move_names() {
+        let mut input = String::new();
+        input.push_str("move_id,local_language_id,name\n");
+        input.push_str("1,1,はたく\n");
+        input.push_str("1,8,Botta\n");
+        input.push_str("825,1,アストラルビット\n");
+        input.push_str("825,8,Schegge Astrali\n");
+
+        let mut output = String::new();
+        output.push_str("\t[1] = \"Botta\",\n");
+        output.push_str("\t[825] = \"Schegge Astrali\",\n");
+
+        let dex = load(Pokedex::load_move_names, &input);
+        let result = Lua.emit(&tables::move_names(&dex));
+
+        assert_eq!(body(&result), module("move_names", &output));
+    }
+
+    #[test]
+    fn test_record() {
+        let mut table = Table::new("stats");
+        table.insert(
+            1,
+            Value::Record(vec![
+                ("hp".to_owned(), Value::Int(45)),
+                ("types".to_owned(), Value::List(vec![Value::Int(12)])),
+            ]),
+        );
+
+        assert_eq!(
+            Lua.emit(&table),
+            header(&[]) + &module("stats", "\t[1] = { hp = 45, types = { 12 } },\n")
+        );
+    }
+
+    #[test]
+    fn test_form_layouts() {
+        let mut input = String::new();
+        input.push_str("id,identifier,species_id,height,weight,base_experience,order,is_default\n");
+        input.push_str("26,raichu,26,8,300,243,35,1\n");
+        input.push_str("10100,raichu-alola,26,7,210,243,36,0\n");
+        let dex = load(Pokedex::load_pokemon, &input);
+        let names = || tables::pokemon_names(&dex);
+
+        let species = tables::with_forms(names(), &dex, FormLayout::Species);
+        assert_eq!(
+            body(&Lua.emit(&species)),
+            module("pokemon_names", "\t[26] = \"Raichu\",\n")
+        );
+
+        let nested = tables::with_forms(names(), &dex, FormLayout::Nested);
+        let mut output = String::new();
+        output.push_str("\t[26] = { default_form = 26, forms = {\n");
+        output.push_str("\t\t[26] = \"Raichu\",\n");
+        output.push_str("\t\t[10100] = \"Alolan Raichu\",\n");
+        output.push_str("\t} },\n");
+        assert_eq!(body(&Lua.emit(&nested)), module("pokemon_names", &output));
+
+        assert_eq!(
+            tables::with_forms(names(), &dex, FormLayout::AllForms),
+            names()
+        );
+    }
+
+    #[test]
+    fn test_header() {
+        let input = "id,identifier,species_id,height,weight,base_experience,order,is_default\n";
+
+        let result = Lua.emit(&tables::pokemon_names(&load(Pokedex::load_pokemon, input)));
+
+        assert!(result.starts_with(
+            "-- Generated from PokeAPI CSV data, do not edit by hand.\n-- Source: test.csv sha256:"
+        ));
+        assert!(result.ends_with("\nlocal pokemon_names = {}\n\nreturn pokemon_names\n"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Farfetch'd"), "\"Farfetch'd\"");
+        assert_eq!(escape("\"Quote\"\\"), "\"\\\"Quote\\\"\\\\\"");
+        assert_eq!(escape("Line\nBreak\r\t"), "\"Line\\nBreak\\r\\t\"");
+        assert_eq!(escape("\u{0}1"), "\"\\0001\"");
+        assert_eq!(escape("ニドラン♀"), "\"ニドラン♀\"");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut table = Table::new("nasty");
+        table.insert(
+            1,
+            Value::Str("Mr. \"Mime\" \\ Jr.\nこんにちは\u{7}7".to_owned()),
+        );
+        table.insert(
+            2,
+            Value::List(vec![Value::Int(-3), Value::Str("end".to_owned())]),
+        );
+        table.insert(
+            3,
+            Value::Map([(8, Value::Int(0)), (17, Value::Int(200))].into()),
+        );
+        table.insert(
+            4,
+            Value::Record(vec![
+                ("end".to_owned(), Value::List(vec![])),
+                ("hp".to_owned(), Value::Int(45)),
+            ]),
+        );
+
+        let parsed = parse::module(&Lua.emit(&table));
+
+        assert_eq!(parsed, [("nasty".to_owned(), table.entries)].into());
+    }
+
+    #[test]
+    fn test_round_trip_csv() {
+        let mut dex = Pokedex::new();
+        let mut diagnostics = Diagnostics::default();
+        let pokemon = CsvSource::new("pokemon.csv", include_str!("../../csv/pokemon.csv"));
+        let move_names = CsvSource::new("move_names.csv", include_str!("../../csv/move_names.csv"));
+        dex.load_pokemon(&pokemon, &mut diagnostics).unwrap();
+        dex.load_move_names(&move_names, &mut diagnostics).unwrap();
+
+        let tables = [tables::pokemon_names(&dex), tables::move_names(&dex)];
+        let parsed = parse::module(&Lua.emit_combined("pokedex", &tables));
+
+        let move_names: BTreeMap<u64, Value> = dex
+            .move_names(8)
+            .map(|(id, name)| (id, Value::Str(name.to_owned())))
+            .collect();
+        assert_eq!(parsed["move_names"], move_names);
+        assert_eq!(parsed["pokemon_names"].len(), dex.pokemon().count());
+        for pokemon in dex.pokemon() {
+            let Value::Str(name) = &parsed["pokemon_names"][&pokemon.id] else {
+                panic!("pokemon {} should have a name", pokemon.id);
+            };
+            assert_eq!(*name, display_name(&pokemon.name));
+        }
+    }
+
+    /// Just enough of a Lua parser to read generated modules back.
+    mod parse {
+        use super::*;
+
+        struct Parser<'a> {
+            input: &'a [u8],
+            pos: usize,
+        }
+
+        /// Tables of a module, keyed by field name or by the local's name.
+        pub fn module(input: &str) -> BTreeMap<String, BTreeMap<u64, Value>> {
+            let mut parser = Parser {
+                input: input.as_bytes(),
+                pos: 0,
+            };
+            let mut tables = BTreeMap::new();
+
+            parser.expect("local");
+            let name = parser.name();
+            parser.expect("=");
+            if parser.peek_str("{}") {
+                // Combined module: `name.field = { ... }` statements
+                parser.expect("{}");
+                while !parser.peek_str("return") {
+                    assert_eq!(parser.name(), name);
+                    parser.expect(".");
+                    let field = parser.name();
+                    parser.expect("=");
+                    tables.insert(field, parser.entries());
+                }
+            } else {
+                tables.insert(name.clone(), parser.entries());
+            }
+            parser.expect("return");
+            assert_eq!(parser.name(), name);
+            parser.skip();
+            assert_eq!(parser.pos, parser.input.len(), "trailing input");
+            tables
+        }
+
+        impl Parser<'_> {
+            fn skip(&mut self) {
+                loop {
+                    while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace()
+                    {
+                        self.pos += 1;
+                    }
+                    if self.input[self.pos..].starts_with(b"--") {
+                        while self.pos < self.input.len() && self.input[self.pos] != b'\n' {
+                            self.pos += 1;
+                        }
+                    } else {
+                        return;
+                    }
+                }
+            }
+
+            fn peek_str(&mut self, token: &str) -> bool {
+                self.skip();
+                self.input[self.pos..].starts_with(token.as_bytes())
+            }
+
+            fn expect(&mut self, token: &str) {
+                assert!(self.peek_str(token), "expected `{token}` at {}", self.pos);
+                self.pos += token.len();
+            }
+
+            fn name(&mut self) -> String {
+                self.skip();
+                let start = self.pos;
+                while self.input[self.pos].is_ascii_alphanumeric() || self.input[self.pos] == b'_' {
+                    self.pos += 1;
+                }
+                String::from_utf8(self.input[start..self.pos].to_vec()).unwrap()
+            }
+
+            fn int(&mut self) -> i64 {
+                self.skip();
+                let start = self.pos;
+                if self.input[self.pos] == b'-' {
+                    self.pos += 1;
+                }
+                while self.input[self.pos].is_ascii_digit() {
+                    self.pos += 1;
+                }
+                std::str::from_utf8(&self.input[start..self.pos])
+                    .unwrap()
+                    .parse()
+                    .unwrap()
+            }
+
+            fn string(&mut self) -> String {
+                self.expect("\"");
+                let mut bytes = Vec::new();
+                loop {
+                    let byte = self.input[self.pos];
+                    self.pos += 1;
+                    match byte {
+                        b'"' => break,
+                        b'\n' => panic!("unescaped newline in string"),
+                        b'\\' => {
+                            let escaped = self.input[self.pos];
+                            self.pos += 1;
+                            match escaped {
+                                b'n' => bytes.push(b'\n'),
+                                b'r' => bytes.push(b'\r'),
+                                b't' => bytes.push(b'\t'),
+                                b'"' | b'\\' => bytes.push(escaped),
+                                b'0'..=b'9' => {
+                                    let digits = &self.input[self.pos - 1..self.pos + 2];
+                                    self.pos += 2;
+                                    bytes.push(
+                                        std::str::from_utf8(digits).unwrap().parse().unwrap(),
+                                    );
+                                }
+                                _ => panic!("unknown escape `\\{}`", escaped as char),
+                            }
+                        }
+                        byte => bytes.push(byte),
+                    }
+                }
+                String::from_utf8(bytes).unwrap()
+            }
+
+            /// A `{ [id] = value, ... }` constructor.
+            fn entries(&mut self) -> BTreeMap<u64, Value> {
+                match self.value() {
+                    Value::Map(entries) => entries,
+                    Value::List(items) if items.is_empty() => BTreeMap::new(),
+                    value => panic!("expected keyed entries, found {value:?}"),
+                }
+            }
+
+            fn value(&mut self) -> Value {
+                self.skip();
+                match self.input[self.pos] {
+                    b'"' => Value::Str(self.string()),
+                    b'{' => self.constructor(),
+                    _ => Value::Int(self.int()),
+                }
+            }
+
+            fn constructor(&mut self) -> Value {
+                self.expect("{");
+                let mut keyed = BTreeMap::new();
+                let mut named = Vec::new();
+                let mut positional = Vec::new();
+                while !self.peek_str("}") {
+                    if self.peek_str("[") {
+                        self.expect("[");
+                        self.skip();
+                        if self.input[self.pos] == b'"' {
+                            let name = self.string();
+                            self.expect("]");
+                            self.expect("=");
+                            named.push((name, self.value()));
+                        } else {
+                            let key = self.int() as u64;
+                            self.expect("]");
+                            self.expect("=");
+                            keyed.insert(key, self.value());
+                        }
+                    } else if self.input[self.pos].is_ascii_alphabetic() {
+                        let name = self.name();
+                        self.expect("=");
+                        named.push((name, self.value()));
+                    } else {
+                        positional.push(self.value());
+                    }
+                    if self.peek_str(",") {
+                        self.expect(",");
+                    }
+                }
+                self.expect("}");
+
+                match (keyed.is_empty(), named.is_empty()) {
+                    (false, true) if positional.is_empty() => Value::Map(keyed),
+                    (true, false) if positional.is_empty() => Value::Record(named),
+                    (true, true) => Value::List(positional),
+                    _ => panic!("mixed table constructor"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Output backends for tables built from the [`Pokedex`](crate::pokedex::Pokedex).
+
+mod c;
+mod json;
+mod lua;
+mod rust;
+
+pub use c::C;
+pub use json::Json;
+pub use lua::Lua;
+pub use rust::Rust;
+
+pub(crate) use json::escape as escape_json;
+
+use crate::csv_source::SourceInfo;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A value stored in a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Str(String),
+    List(Vec<Value>),
+    Map(BTreeMap<u64, Value>),
+    Record(Vec<(String, Value)>),
+}
+
+/// A named table of values keyed by id, independent of the output format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub entries: BTreeMap<u64, Value>,
+    /// Files the table was generated from.
+    pub sources: Vec<SourceInfo>,
+}
+
+impl Table {
+    pub fn new(name: impl Into<String>) -> Self {
+        Table {
+            name: name.into(),
+            entries: BTreeMap::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, value: Value) {
+        self.entries.insert(key, value);
+    }
+
+    /// Shape shared by every entry, used by the typed backends.
+    pub fn shape(&self) -> Shape {
+        self.entries
+            .values()
+            .fold(Shape::Unknown, |acc, value| acc.merge(Shape::of(value)))
+    }
+}
+
+/// Type of a [`Value`], with empty lists and maps resolved by their siblings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Unknown,
+    Int,
+    Str,
+    List(Box<Shape>),
+    Map(Box<Shape>),
+    Record(Vec<(String, Shape)>),
+}
+
+impl Shape {
+    pub fn of(value: &Value) -> Shape {
+        match value {
+            Value::Int(_) => Shape::Int,
+            Value::Str(_) => Shape::Str,
+            Value::List(items) => Shape::List(Box::new(
+                items
+                    .iter()
+                    .fold(Shape::Unknown, |acc, item| acc.merge(Shape::of(item))),
+            )),
+            Value::Map(entries) => Shape::Map(Box::new(
+                entries
+                    .values()
+                    .fold(Shape::Unknown, |acc, item| acc.merge(Shape::of(item))),
+            )),
+            Value::Record(fields) => Shape::Record(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Shape::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Combines two shapes, keeping `self` when they disagree.
+    pub fn merge(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Unknown, other) => other,
+            (Shape::List(a), Shape::List(b)) => Shape::List(Box::new(a.merge(*b))),
+            (Shape::Map(a), Shape::Map(b)) => Shape::Map(Box::new(a.merge(*b))),
+            (Shape::Record(mut fields), Shape::Record(others)) => {
+                for (name, shape) in others {
+                    match fields.iter_mut().find(|(field, _)| *field == name) {
+                        Some((_, field)) => {
+                            *field = std::mem::replace(field, Shape::Unknown).merge(shape)
+                        }
+                        None => fields.push((name, shape)),
+                    }
+                }
+                Shape::Record(fields)
+            }
+            (shape, _) => shape,
+        }
+    }
+
+    pub fn is_scalar(&self) -> bool {
+        matches!(self, Shape::Unknown | Shape::Int | Shape::Str)
+    }
+}
+
+/// Renders a [`Table`] as source text for one target language.
+pub trait Emitter {
+    /// Extension of the generated file, without the dot.
+    fn extension(&self) -> &'static str;
+
+    fn emit(&self, table: &Table) -> String;
+}
+
+/// Output formats selectable at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Lua,
+    Json,
+    C,
+    Rust,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Lua, Format::Json, Format::C, Format::Rust];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Lua => "lua",
+            Format::Json => "json",
+            Format::C => "c",
+            Format::Rust => "rust",
+        }
+    }
+
+    pub fn emitter(self) -> &'static dyn Emitter {
+        match self {
+            Format::Lua => &Lua,
+            Format::Json => &Json,
+            Format::C => &C,
+            Format::Rust => &Rust,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| format!("unknown format `{s}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_resolves_empty_lists() {
+        let mut table = Table::new("weaknesses");
+        table.insert(1, Value::List(vec![]));
+        table.insert(2, Value::List(vec![Value::Int(3)]));
+
+        assert_eq!(table.shape(), Shape::List(Box::new(Shape::Int)));
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("c".parse::<Format>(), Ok(Format::C));
+        assert_eq!(
+            "rust".parse::<Format>().unwrap().emitter().extension(),
+            "rs"
+        );
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
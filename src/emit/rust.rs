@@ -0,0 +1,199 @@
+use super::{Emitter, Shape, Table, Value};
+
+/// Rust source with a sorted `pub static` slice and a lookup function.
+pub struct Rust;
+
+impl Emitter for Rust {
+    fn extension(&self) -> &'static str {
+        "rs"
+    }
+
+    fn emit(&self, table: &Table) -> String {
+        let shape = table.shape();
+        let name = identifier(&table.name);
+        let upper = name.to_uppercase();
+
+        let entry_name = format!("{}Entry", camel_case(&name));
+        let mut structs = Vec::new();
+        let value_type = rust_type(&shape, &entry_name, &mut structs);
+
+        let mut out = String::new();
+        out.push_str(&format!("// Generated `{}` table.\n\n", table.name));
+        for definition in structs {
+            out.push_str(&definition);
+            out.push('\n');
+        }
+
+        out.push_str(&format!(
+            "pub static {upper}: &[(u64, {value_type})] = &[\n"
+        ));
+        for (key, value) in &table.entries {
+            out.push_str(&format!(
+                "    ({key}, {}),\n",
+                rust_value(value, &shape, &entry_name)
+            ));
+        }
+        out.push_str("];\n\n");
+
+        out.push_str(&format!(
+            "/// Looks up an entry of [`{upper}`] by id.\n\
+             pub fn {name}(id: u64) -> Option<{value_type}> {{\n    \
+                 {upper}\n        \
+                     .binary_search_by_key(&id, |(key, _)| *key)\n        \
+                     .ok()\n        \
+                     .map(|index| {upper}[index].1)\n\
+             }}\n"
+        ));
+        out
+    }
+}
+
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| part[0..1].to_uppercase() + &part[1..])
+        .collect()
+}
+
+/// Type of `shape`, pushing the definition of every record struct it needs.
+fn rust_type(shape: &Shape, struct_name: &str, structs: &mut Vec<String>) -> String {
+    match shape {
+        Shape::Unknown | Shape::Int => "i64".to_owned(),
+        Shape::Str => "&'static str".to_owned(),
+        Shape::List(item) => format!("&'static [{}]", rust_type(item, struct_name, structs)),
+        Shape::Map(item) => format!(
+            "&'static [(u64, {})]",
+            rust_type(item, struct_name, structs)
+        ),
+        Shape::Record(fields) => {
+            let mut definition =
+                format!("#[derive(Debug, Clone, Copy)]\npub struct {struct_name} {{\n");
+            for (field, shape) in fields {
+                let field_type = rust_type(
+                    shape,
+                    &format!("{struct_name}{}", camel_case(&identifier(field))),
+                    structs,
+                );
+                definition.push_str(&format!("    pub {}: {field_type},\n", identifier(field)));
+            }
+            definition.push_str("}\n");
+            structs.push(definition);
+            struct_name.to_owned()
+        }
+    }
+}
+
+/// Literal for `value`, naming record structs the same way as [`rust_type`].
+fn rust_value(value: &Value, shape: &Shape, struct_name: &str) -> String {
+    match (shape, value) {
+        (Shape::Unknown | Shape::Int, Value::Int(value)) => value.to_string(),
+        (Shape::Str, Value::Str(value)) => format!("{value:?}"),
+        (Shape::List(item), Value::List(values)) => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|value| rust_value(value, item, struct_name))
+                .collect();
+            format!("&[{}]", values.join(", "))
+        }
+        (Shape::Map(item), Value::Map(entries)) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("({key}, {})", rust_value(value, item, struct_name)))
+                .collect();
+            format!("&[{}]", entries.join(", "))
+        }
+        (Shape::Record(fields), Value::Record(values)) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, shape)| {
+                    let field_struct = format!("{struct_name}{}", camel_case(&identifier(name)));
+                    let value = match values.iter().find(|(field, _)| field == name) {
+                        Some((_, value)) => rust_value(value, shape, &field_struct),
+                        None => default_value(shape, &field_struct),
+                    };
+                    format!("{}: {value}", identifier(name))
+                })
+                .collect();
+            format!("{struct_name} {{ {} }}", fields.join(", "))
+        }
+        (shape, _) => default_value(shape, struct_name),
+    }
+}
+
+fn default_value(shape: &Shape, struct_name: &str) -> String {
+    match shape {
+        Shape::Unknown | Shape::Int => "0".to_owned(),
+        Shape::Str => "\"\"".to_owned(),
+        Shape::List(_) | Shape::Map(_) => "&[]".to_owned(),
+        Shape::Record(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, shape)| {
+                    let field_struct = format!("{struct_name}{}", camel_case(&identifier(name)));
+                    format!(
+                        "{}: {}",
+                        identifier(name),
+                        default_value(shape, &field_struct)
+                    )
+                })
+                .collect();
+            format!("{struct_name} {{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_strings() {
+        let mut table = Table::new("pokemon_names");
+        table.insert(1, Value::Str("Bulbasaur".to_owned()));
+        table.insert(29, Value::Str("Nidoran♀".to_owned()));
+
+        let mut output = String::new();
+        output.push_str("// Generated `pokemon_names` table.\n\n");
+        output.push_str("pub static POKEMON_NAMES: &[(u64, &'static str)] = &[\n");
+        output.push_str("    (1, \"Bulbasaur\"),\n");
+        output.push_str("    (29, \"Nidoran♀\"),\n");
+        output.push_str("];\n\n");
+        output.push_str("/// Looks up an entry of [`POKEMON_NAMES`] by id.\n");
+        output.push_str("pub fn pokemon_names(id: u64) -> Option<&'static str> {\n");
+        output.push_str("    POKEMON_NAMES\n");
+        output.push_str("        .binary_search_by_key(&id, |(key, _)| *key)\n");
+        output.push_str("        .ok()\n");
+        output.push_str("        .map(|index| POKEMON_NAMES[index].1)\n");
+        output.push_str("}\n");
+
+        assert_eq!(Rust.emit(&table), output);
+    }
+
+    #[test]
+    fn test_rust_records() {
+        let mut table = Table::new("pokemon_stats");
+        table.insert(
+            1,
+            Value::Record(vec![
+                ("hp".to_owned(), Value::Int(45)),
+                ("types".to_owned(), Value::List(vec![Value::Int(12)])),
+            ]),
+        );
+        table.insert(2, Value::Record(vec![("hp".to_owned(), Value::Int(60))]));
+
+        let output = Rust.emit(&table);
+
+        assert!(output.contains(
+            "pub struct PokemonStatsEntry {\n    pub hp: i64,\n    pub types: &'static [i64],\n}\n"
+        ));
+        assert!(output.contains("pub static POKEMON_STATS: &[(u64, PokemonStatsEntry)] = &[\n"));
+        assert!(output.contains("    (1, PokemonStatsEntry { hp: 45, types: &[12] }),\n"));
+        assert!(output.contains("    (2, PokemonStatsEntry { hp: 60, types: &[] }),\n"));
+    }
+}
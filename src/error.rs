@@ -0,0 +1,126 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Position of a CSV record inside its source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    /// 1-based line the record starts on.
+    pub line: u64,
+    /// 0-based record index, not counting the header.
+    pub record: u64,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io { path: PathBuf, source: io::Error },
+    /// A record could not be parsed or deserialized.
+    MalformedRow {
+        at: Location,
+        column: Option<String>,
+        message: String,
+    },
+    /// A Pokemon has a slot-2 type but no slot-1 type.
+    MissingPrimaryType { at: Location, pokemon_id: u64 },
+    /// A type id is not present in the type chart.
+    UnknownType { at: Location, type_id: u64 },
+    /// A record asked for by id or identifier is not in the loaded data.
+    NotFound { kind: &'static str, key: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Io { .. } | Error::NotFound { .. } => None,
+            Error::MalformedRow { at, .. }
+            | Error::MissingPrimaryType { at, .. }
+            | Error::UnknownType { at, .. } => Some(at),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Error::MalformedRow {
+                at,
+                column: Some(column),
+                message,
+            } => write!(f, "{at}: malformed row, column `{column}`: {message}"),
+            Error::MalformedRow {
+                at,
+                column: None,
+                message,
+            } => write!(f, "{at}: malformed row: {message}"),
+            Error::MissingPrimaryType { at, pokemon_id } => write!(
+                f,
+                "{at}: pokemon {pokemon_id} has a slot-2 type but no slot-1 type"
+            ),
+            Error::UnknownType { at, type_id } => write!(f, "{at}: unknown type id {type_id}"),
+            Error::NotFound { kind, key } => write!(f, "no {kind} `{key}` in the loaded data"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// What to do when a row fails to parse or validate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnBadRow {
+    #[default]
+    Fail,
+    SkipAndWarn,
+}
+
+/// Collects row errors according to an [`OnBadRow`] policy.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    on_bad_row: OnBadRow,
+    warnings: Vec<Error>,
+}
+
+impl Diagnostics {
+    pub fn new(on_bad_row: OnBadRow) -> Self {
+        Diagnostics {
+            on_bad_row,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns the error back when failing, or records it as a warning when skipping.
+    pub fn report(&mut self, err: Error) -> Result<()> {
+        match self.on_bad_row {
+            OnBadRow::Fail => Err(err),
+            OnBadRow::SkipAndWarn => {
+                self.warnings.push(err);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.warnings)
+    }
+}
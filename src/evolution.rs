@@ -0,0 +1,144 @@
+//! Evolution chains built from `pokemon_species.csv` and `pokemon_evolution.csv`.
+
+use std::fmt;
+
+/// What starts an evolution, with the `evolution_trigger_id` as discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EvolutionTrigger {
+    LevelUp = 1,
+    Trade,
+    UseItem,
+    Shed,
+    Spin,
+    TowerOfDarkness,
+    TowerOfWaters,
+    ThreeCriticalHits,
+    TakeDamage,
+    Other,
+    AgileStyleMove,
+    StrongStyleMove,
+    RecoilDamage,
+}
+
+impl EvolutionTrigger {
+    pub const ALL: [EvolutionTrigger; 13] = [
+        EvolutionTrigger::LevelUp,
+        EvolutionTrigger::Trade,
+        EvolutionTrigger::UseItem,
+        EvolutionTrigger::Shed,
+        EvolutionTrigger::Spin,
+        EvolutionTrigger::TowerOfDarkness,
+        EvolutionTrigger::TowerOfWaters,
+        EvolutionTrigger::ThreeCriticalHits,
+        EvolutionTrigger::TakeDamage,
+        EvolutionTrigger::Other,
+        EvolutionTrigger::AgileStyleMove,
+        EvolutionTrigger::StrongStyleMove,
+        EvolutionTrigger::RecoilDamage,
+    ];
+
+    pub fn id(self) -> u64 {
+        self as u64
+    }
+
+    pub fn from_id(id: u64) -> Option<EvolutionTrigger> {
+        EvolutionTrigger::ALL
+            .into_iter()
+            .find(|trigger| trigger.id() == id)
+    }
+
+    /// Identifier as spelled by PokeAPI, e.g. `level-up`.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            EvolutionTrigger::LevelUp => "level-up",
+            EvolutionTrigger::Trade => "trade",
+            EvolutionTrigger::UseItem => "use-item",
+            EvolutionTrigger::Shed => "shed",
+            EvolutionTrigger::Spin => "spin",
+            EvolutionTrigger::TowerOfDarkness => "tower-of-darkness",
+            EvolutionTrigger::TowerOfWaters => "tower-of-waters",
+            EvolutionTrigger::ThreeCriticalHits => "three-critical-hits",
+            EvolutionTrigger::TakeDamage => "take-damage",
+            EvolutionTrigger::Other => "other",
+            EvolutionTrigger::AgileStyleMove => "agile-style-move",
+            EvolutionTrigger::StrongStyleMove => "strong-style-move",
+            EvolutionTrigger::RecoilDamage => "recoil-damage",
+        }
+    }
+}
+
+impl fmt::Display for EvolutionTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.identifier())
+    }
+}
+
+/// One way of evolving into a species; most conditions are optional and
+/// PokeAPI lists one row per method when there are several.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvolutionMethod {
+    pub trigger: EvolutionTrigger,
+    pub minimum_level: Option<u64>,
+    /// Item used on the Pokemon, for [`EvolutionTrigger::UseItem`].
+    pub trigger_item_id: Option<u64>,
+    pub held_item_id: Option<u64>,
+    pub known_move_id: Option<u64>,
+    pub minimum_happiness: Option<u64>,
+    /// `day` or `night`.
+    pub time_of_day: Option<String>,
+    /// Species to trade with, for Karrablast and Shelmet.
+    pub trade_species_id: Option<u64>,
+}
+
+/// A stage of an evolution chain and everything it evolves into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvolutionNode {
+    pub species_id: u64,
+    /// How the previous stage evolves into this one, empty for the first
+    /// stage or when `pokemon_evolution.csv` is not loaded.
+    pub methods: Vec<EvolutionMethod>,
+    /// Next stages in species id order, several for branching chains.
+    pub evolves_to: Vec<EvolutionNode>,
+}
+
+impl EvolutionNode {
+    /// The species of this stage and every later one, depth first.
+    pub fn species(&self) -> Vec<u64> {
+        let mut species = vec![self.species_id];
+        for next in &self.evolves_to {
+            species.extend(next.species());
+        }
+        species
+    }
+
+    /// The stage of `species_id` in this subtree.
+    pub fn find(&self, species_id: u64) -> Option<&EvolutionNode> {
+        if self.species_id == species_id {
+            return Some(self);
+        }
+        self.evolves_to
+            .iter()
+            .find_map(|next| next.find(species_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_ids() {
+        for trigger in EvolutionTrigger::ALL {
+            assert_eq!(EvolutionTrigger::from_id(trigger.id()), Some(trigger));
+        }
+        assert_eq!(
+            EvolutionTrigger::from_id(3),
+            Some(EvolutionTrigger::UseItem)
+        );
+        assert_eq!(EvolutionTrigger::from_id(0), None);
+        assert_eq!(
+            EvolutionTrigger::TowerOfWaters.to_string(),
+            "tower-of-waters"
+        );
+    }
+}
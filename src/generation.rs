@@ -0,0 +1,199 @@
+//! Main-series game generations and the type chart of each era.
+
+use crate::type_chart::TypeChart;
+use crate::types::{Effectiveness, Type};
+use std::fmt;
+use std::str::FromStr;
+
+/// Highest national dex number introduced by each generation.
+const LAST_NATIONAL_DEX: [u64; 9] = [151, 251, 386, 493, 649, 721, 809, 905, 1025];
+
+/// Matchups that changed, with their old value and the generation the
+/// current one applies from.
+const CHART_CHANGES: [(Type, Type, Effectiveness, u8); 6] = [
+    (Type::Ghost, Type::Psychic, Effectiveness::IMMUNE, 2),
+    (Type::Bug, Type::Poison, Effectiveness::DOUBLE, 2),
+    (Type::Poison, Type::Bug, Effectiveness::DOUBLE, 2),
+    (Type::Ice, Type::Fire, Effectiveness::NEUTRAL, 2),
+    (Type::Ghost, Type::Steel, Effectiveness::HALF, 6),
+    (Type::Dark, Type::Steel, Effectiveness::HALF, 6),
+];
+
+/// A main-series generation, 1 to 9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Generation(u8);
+
+impl Generation {
+    pub const LATEST: Generation = Generation(LAST_NATIONAL_DEX.len() as u8);
+
+    pub fn new(number: u64) -> Option<Generation> {
+        match number {
+            1..=9 => Some(Generation(number as u8)),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u64 {
+        self.0.into()
+    }
+
+    pub fn last_national_dex(self) -> u64 {
+        LAST_NATIONAL_DEX[usize::from(self.0) - 1]
+    }
+
+    /// The generation a species was introduced in, by national dex number.
+    pub fn of_species(national_dex: u64) -> Option<Generation> {
+        if national_dex == 0 {
+            return None;
+        }
+        LAST_NATIONAL_DEX
+            .iter()
+            .position(|last| national_dex <= *last)
+            .map(|index| Generation(index as u8 + 1))
+    }
+
+    pub fn has_type(self, type_: Type) -> bool {
+        match type_ {
+            Type::Dark | Type::Steel => self.0 >= 2,
+            Type::Fairy => self.0 >= 6,
+            _ => true,
+        }
+    }
+
+    /// `chart` as it was in this generation: later types are removed and
+    /// changed matchups get their old value back.
+    pub fn type_chart(self, chart: &TypeChart) -> TypeChart {
+        let mut historical = TypeChart::empty();
+        let types = chart.types().iter().filter(|type_| self.has_type(**type_));
+        for attacking in types.clone() {
+            for defending in types.clone() {
+                if let Some(effectiveness) = chart.factor(*attacking, *defending) {
+                    historical.set(*attacking, *defending, effectiveness);
+                }
+            }
+        }
+
+        for (attacking, defending, effectiveness, since) in CHART_CHANGES {
+            if self.0 < since && historical.contains(attacking) && historical.contains(defending) {
+                historical.set(attacking, defending, effectiveness);
+            }
+        }
+        historical
+    }
+}
+
+impl fmt::Display for Generation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "generation {}", self.0)
+    }
+}
+
+impl FromStr for Generation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .ok()
+            .and_then(Generation::new)
+            .ok_or_else(|| format!("unknown generation `{s}`, expected 1 to 9"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(generation: u64) -> TypeChart {
+        Generation::new(generation)
+            .unwrap()
+            .type_chart(TypeChart::standard())
+    }
+
+    #[test]
+    fn test_of_species() {
+        assert_eq!(Generation::of_species(0), None);
+        assert_eq!(Generation::of_species(1), Generation::new(1));
+        assert_eq!(Generation::of_species(151), Generation::new(1));
+        assert_eq!(Generation::of_species(152), Generation::new(2));
+        assert_eq!(Generation::of_species(493), Generation::new(4));
+        assert_eq!(Generation::of_species(905), Generation::new(8));
+        assert_eq!(Generation::of_species(1025), Some(Generation::LATEST));
+        assert_eq!(Generation::of_species(1026), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3".parse(), Ok(Generation(3)));
+        assert!("0".parse::<Generation>().is_err());
+        assert!("10".parse::<Generation>().is_err());
+        assert!("iii".parse::<Generation>().is_err());
+    }
+
+    #[test]
+    fn test_generation_1_chart() {
+        let chart = chart(1);
+
+        assert_eq!(chart.types().len(), 15);
+        for type_ in [Type::Dark, Type::Steel, Type::Fairy] {
+            assert!(!chart.contains(type_));
+        }
+        assert_eq!(
+            chart.factor(Type::Ghost, Type::Psychic),
+            Some(Effectiveness::IMMUNE)
+        );
+        assert_eq!(
+            chart.factor(Type::Bug, Type::Poison),
+            Some(Effectiveness::DOUBLE)
+        );
+        assert_eq!(
+            chart.factor(Type::Poison, Type::Bug),
+            Some(Effectiveness::DOUBLE)
+        );
+        assert_eq!(
+            chart.factor(Type::Ice, Type::Fire),
+            Some(Effectiveness::NEUTRAL)
+        );
+        // Unchanged matchups are kept
+        assert_eq!(
+            chart.factor(Type::Fire, Type::Grass),
+            Some(Effectiveness::DOUBLE)
+        );
+    }
+
+    #[test]
+    fn test_generation_2_to_5_chart() {
+        for generation in 2..=5 {
+            let chart = chart(generation);
+
+            assert_eq!(chart.types().len(), 17);
+            assert!(chart.contains(Type::Steel) && !chart.contains(Type::Fairy));
+            assert_eq!(
+                chart.factor(Type::Ghost, Type::Steel),
+                Some(Effectiveness::HALF)
+            );
+            assert_eq!(
+                chart.factor(Type::Dark, Type::Steel),
+                Some(Effectiveness::HALF)
+            );
+            assert_eq!(
+                chart.factor(Type::Ghost, Type::Psychic),
+                Some(Effectiveness::DOUBLE)
+            );
+            assert_eq!(
+                chart.factor(Type::Bug, Type::Poison),
+                Some(Effectiveness::HALF)
+            );
+            assert_eq!(
+                chart.factor(Type::Ice, Type::Fire),
+                Some(Effectiveness::HALF)
+            );
+        }
+    }
+
+    #[test]
+    fn test_generation_6_onwards_chart() {
+        for generation in 6..=9 {
+            assert_eq!(&chart(generation), TypeChart::standard());
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! PokeAPI languages, by `local_language_id` or ISO code.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The languages the PokeAPI names tables are translated to, with their
+/// `local_language_id` as discriminants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Language {
+    /// Japanese in kana.
+    JaHrkt = 1,
+    Ko = 3,
+    ZhHant = 4,
+    Fr = 5,
+    De = 6,
+    Es = 7,
+    It = 8,
+    En = 9,
+    /// Japanese with kanji.
+    Ja = 11,
+    ZhHans = 12,
+}
+
+impl Language {
+    pub const ALL: [Language; 10] = [
+        Language::JaHrkt,
+        Language::Ko,
+        Language::ZhHant,
+        Language::Fr,
+        Language::De,
+        Language::Es,
+        Language::It,
+        Language::En,
+        Language::Ja,
+        Language::ZhHans,
+    ];
+
+    pub fn id(self) -> u64 {
+        self as u64
+    }
+
+    pub fn from_id(id: u64) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.id() == id)
+    }
+
+    /// Code as spelled by PokeAPI, e.g. `ja-Hrkt` or `it`.
+    pub fn iso(self) -> &'static str {
+        match self {
+            Language::JaHrkt => "ja-Hrkt",
+            Language::Ko => "ko",
+            Language::ZhHant => "zh-Hant",
+            Language::Fr => "fr",
+            Language::De => "de",
+            Language::Es => "es",
+            Language::It => "it",
+            Language::En => "en",
+            Language::Ja => "ja",
+            Language::ZhHans => "zh-Hans",
+        }
+    }
+
+    pub fn from_iso(iso: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.iso().eq_ignore_ascii_case(iso))
+    }
+
+    /// The code as a suffix usable in identifiers, e.g. `zh_hans`.
+    pub fn suffix(self) -> String {
+        self.iso().to_lowercase().replace('-', "_")
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.iso())
+    }
+}
+
+/// Parses an ISO code such as `it` or a PokeAPI id such as `8`.
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u64>()
+            .ok()
+            .and_then(Language::from_id)
+            .or_else(|| Language::from_iso(s))
+            .ok_or_else(|| format!("unknown language `{s}`"))
+    }
+}
+
+/// Parses a comma-separated fallback chain such as `it,en`.
+pub fn parse_chain(s: &str) -> Result<Vec<Language>, String> {
+    let mut chain: Vec<Language> = Vec::new();
+    for language in s.split(',') {
+        let language = language.trim().parse()?;
+        if !chain.contains(&language) {
+            chain.push(language);
+        }
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("it".parse(), Ok(Language::It));
+        assert_eq!("8".parse(), Ok(Language::It));
+        assert_eq!("ZH-hans".parse(), Ok(Language::ZhHans));
+        assert_eq!("1".parse(), Ok(Language::JaHrkt));
+        assert!("2".parse::<Language>().is_err());
+        assert!("italian".parse::<Language>().is_err());
+        for language in Language::ALL {
+            assert_eq!(language.iso().parse(), Ok(language));
+            assert_eq!(Language::from_id(language.id()), Some(language));
+        }
+    }
+
+    #[test]
+    fn test_chain() {
+        assert_eq!(parse_chain("it, en"), Ok(vec![Language::It, Language::En]));
+        assert_eq!(parse_chain("9,en"), Ok(vec![Language::En]));
+        assert!(parse_chain("it,").is_err());
+        assert_eq!(Language::JaHrkt.suffix(), "ja_hrkt");
+    }
+}
@@ -1,3 +1,18 @@
+pub mod coverage;
+pub mod csv_source;
+pub mod damage;
+pub mod display_name;
+pub mod emit;
+pub mod error;
+pub mod evolution;
+pub mod generation;
+pub mod language;
+pub mod pokedex;
+pub mod tables;
+pub mod team;
+pub mod type_chart;
+pub mod types;
+
 use std::collections::BTreeMap;
 
 pub fn get_type_efficacy() -> BTreeMap<u64, BTreeMap<u64, u64>> {
@@ -0,0 +1,1632 @@
+use crate::csv_source::{self, CsvSource, Row, SourceInfo};
+use crate::error::{Diagnostics, Error, Result};
+use crate::evolution::{EvolutionMethod, EvolutionNode, EvolutionTrigger};
+use crate::generation::Generation;
+use crate::language::Language;
+use crate::type_chart::TypeChart;
+use crate::types::Type;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+/// A row of `pokemon.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pokemon {
+    pub id: u64,
+    #[serde(rename = "identifier")]
+    pub name: String,
+    /// National dex number of the species.
+    pub species_id: u64,
+    /// In decimetres.
+    pub height: u64,
+    /// In hectograms.
+    pub weight: u64,
+    /// Empty in PokeAPI for a few recent Pokemon.
+    pub base_experience: Option<u64>,
+    /// Whether this is the form the species is listed as, false for
+    /// megas, regional forms and the like.
+    #[serde(deserialize_with = "csv_source::flag")]
+    pub is_default: bool,
+}
+
+/// A row of `pokemon_species.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonSpecies {
+    pub id: u64,
+    pub identifier: String,
+    pub generation_id: u64,
+    /// Previous stage of the evolution chain, empty for first stages.
+    pub evolves_from_species_id: Option<u64>,
+}
+
+/// A row of `pokemon_evolution.csv`, the conditions PokeAPI exports beyond
+/// these are ignored.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonEvolution {
+    pub id: u64,
+    pub evolved_species_id: u64,
+    pub evolution_trigger_id: u64,
+    pub trigger_item_id: Option<u64>,
+    pub minimum_level: Option<u64>,
+    pub held_item_id: Option<u64>,
+    pub time_of_day: Option<String>,
+    pub known_move_id: Option<u64>,
+    pub minimum_happiness: Option<u64>,
+    pub trade_species_id: Option<u64>,
+}
+
+/// A row of `pokemon_types.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonType {
+    #[serde(rename = "pokemon_id")]
+    pub id: u64,
+    pub type_id: u64,
+    pub slot: u64,
+}
+
+/// A row of `pokemon_species_names.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonSpeciesName {
+    #[serde(rename = "pokemon_species_id")]
+    pub species_id: u64,
+    #[serde(rename = "local_language_id")]
+    pub language: u64,
+    pub name: String,
+}
+
+/// A row of `pokemon_types_past.csv`: the types a Pokemon had up to and
+/// including `generation_id`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonTypePast {
+    pub pokemon_id: u64,
+    pub generation_id: u64,
+    pub type_id: u64,
+    pub slot: u64,
+}
+
+/// A row of `pokemon_stats.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonStat {
+    pub pokemon_id: u64,
+    pub stat_id: u64,
+    pub base_stat: u64,
+    pub effort: u64,
+}
+
+/// A row of `pokemon_abilities.csv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct PokemonAbility {
+    pub pokemon_id: u64,
+    pub ability_id: u64,
+    #[serde(deserialize_with = "csv_source::flag")]
+    pub is_hidden: bool,
+    pub slot: u64,
+}
+
+/// The six base stats of a Pokemon.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BaseStats {
+    pub hp: u64,
+    pub attack: u64,
+    pub defense: u64,
+    pub special_attack: u64,
+    pub special_defense: u64,
+    pub speed: u64,
+}
+
+impl BaseStats {
+    /// The field for a PokeAPI `stat_id`, 1 being HP and 6 Speed.
+    pub fn get_mut(&mut self, stat_id: u64) -> Option<&mut u64> {
+        match stat_id {
+            1 => Some(&mut self.hp),
+            2 => Some(&mut self.attack),
+            3 => Some(&mut self.defense),
+            4 => Some(&mut self.special_attack),
+            5 => Some(&mut self.special_defense),
+            6 => Some(&mut self.speed),
+            _ => None,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.hp
+            + self.attack
+            + self.defense
+            + self.special_attack
+            + self.special_defense
+            + self.speed
+    }
+}
+
+/// A row of `type_efficacy.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypeEfficacy {
+    #[serde(rename = "damage_type_id")]
+    pub damage: u64,
+    #[serde(rename = "target_type_id")]
+    pub target: u64,
+    #[serde(rename = "damage_factor")]
+    pub factor: u64,
+}
+
+/// A row of `moves.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Move {
+    pub id: u64,
+    pub identifier: String,
+    pub generation_id: u64,
+    pub type_id: u64,
+    /// `None` for status moves and moves with a variable power.
+    pub power: Option<u64>,
+    pub pp: Option<u64>,
+    /// `None` for moves that never miss.
+    pub accuracy: Option<u64>,
+    pub priority: i64,
+    pub target_id: u64,
+    pub damage_class_id: u64,
+    pub effect_id: Option<u64>,
+    pub effect_chance: Option<u64>,
+    pub contest_type_id: Option<u64>,
+    pub contest_effect_id: Option<u64>,
+    pub super_contest_effect_id: Option<u64>,
+}
+
+impl Move {
+    pub fn damage_class(&self) -> Option<DamageClass> {
+        DamageClass::from_id(self.damage_class_id)
+    }
+}
+
+/// Criteria a move must meet to be selected, every one of them optional.
+///
+/// The default filter selects every move.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveFilter {
+    /// Only moves with a base power.
+    pub damaging_only: bool,
+    pub damage_class: Option<DamageClass>,
+    pub min_generation: Option<u64>,
+    pub max_generation: Option<u64>,
+    /// Moves that never miss always pass.
+    pub min_accuracy: Option<u64>,
+    pub min_priority: Option<i64>,
+    pub max_priority: Option<i64>,
+}
+
+impl MoveFilter {
+    /// Moves with a base power, as exported by the `moves` table.
+    pub fn damaging() -> MoveFilter {
+        MoveFilter {
+            damaging_only: true,
+            ..MoveFilter::default()
+        }
+    }
+
+    pub fn matches(&self, move_: &Move) -> bool {
+        (!self.damaging_only || move_.power.is_some())
+            && self
+                .damage_class
+                .is_none_or(|class| move_.damage_class() == Some(class))
+            && self
+                .min_generation
+                .is_none_or(|min| move_.generation_id >= min)
+            && self
+                .max_generation
+                .is_none_or(|max| move_.generation_id <= max)
+            && self
+                .min_accuracy
+                .is_none_or(|min| move_.accuracy.is_none_or(|accuracy| accuracy >= min))
+            && self.min_priority.is_none_or(|min| move_.priority >= min)
+            && self.max_priority.is_none_or(|max| move_.priority <= max)
+    }
+}
+
+/// Whether a move deals damage, and with which pair of stats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DamageClass {
+    Status = 1,
+    Physical,
+    Special,
+}
+
+impl DamageClass {
+    pub fn from_id(id: u64) -> Option<DamageClass> {
+        match id {
+            1 => Some(DamageClass::Status),
+            2 => Some(DamageClass::Physical),
+            3 => Some(DamageClass::Special),
+            _ => None,
+        }
+    }
+
+    /// Lowercase PokeAPI identifier, e.g. `physical`.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            DamageClass::Status => "status",
+            DamageClass::Physical => "physical",
+            DamageClass::Special => "special",
+        }
+    }
+}
+
+impl std::str::FromStr for DamageClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [
+            DamageClass::Status,
+            DamageClass::Physical,
+            DamageClass::Special,
+        ]
+        .into_iter()
+        .find(|class| class.identifier().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("unknown damage class `{s}`"))
+    }
+}
+
+/// A row of `pokemon_moves.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PokemonMove {
+    pub pokemon_id: u64,
+    pub version_group_id: u64,
+    pub move_id: u64,
+    pub pokemon_move_method_id: u64,
+    /// 0 for moves not learnt by leveling up.
+    pub level: u64,
+    /// Position among the moves learnt at the same level, when it matters.
+    pub order: Option<u64>,
+}
+
+/// How a Pokemon learns a move, with the `pokemon_move_method_id` as
+/// discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LearnMethod {
+    LevelUp = 1,
+    Egg,
+    Tutor,
+    Machine,
+    StadiumSurfingPikachu,
+    LightBallEgg,
+    ColosseumPurification,
+    XdShadow,
+    XdPurification,
+    FormChange,
+    ZygardeCube,
+}
+
+impl LearnMethod {
+    pub const ALL: [LearnMethod; 11] = [
+        LearnMethod::LevelUp,
+        LearnMethod::Egg,
+        LearnMethod::Tutor,
+        LearnMethod::Machine,
+        LearnMethod::StadiumSurfingPikachu,
+        LearnMethod::LightBallEgg,
+        LearnMethod::ColosseumPurification,
+        LearnMethod::XdShadow,
+        LearnMethod::XdPurification,
+        LearnMethod::FormChange,
+        LearnMethod::ZygardeCube,
+    ];
+
+    pub fn from_id(id: u64) -> Option<LearnMethod> {
+        LearnMethod::ALL
+            .into_iter()
+            .find(|method| *method as u64 == id)
+    }
+
+    /// PokeAPI identifier, e.g. `level-up`.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            LearnMethod::LevelUp => "level-up",
+            LearnMethod::Egg => "egg",
+            LearnMethod::Tutor => "tutor",
+            LearnMethod::Machine => "machine",
+            LearnMethod::StadiumSurfingPikachu => "stadium-surfing-pikachu",
+            LearnMethod::LightBallEgg => "light-ball-egg",
+            LearnMethod::ColosseumPurification => "colosseum-purification",
+            LearnMethod::XdShadow => "xd-shadow",
+            LearnMethod::XdPurification => "xd-purification",
+            LearnMethod::FormChange => "form-change",
+            LearnMethod::ZygardeCube => "zygarde-cube",
+        }
+    }
+}
+
+impl std::str::FromStr for LearnMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        LearnMethod::ALL
+            .into_iter()
+            .find(|method| method.identifier().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown learn method `{s}`"))
+    }
+}
+
+/// A move in a learnset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LearnableMove {
+    pub move_id: u64,
+    pub method: LearnMethod,
+    /// 0 unless learnt by leveling up.
+    pub level: u64,
+    pub order: Option<u64>,
+}
+
+/// A row of `move_names.csv`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveName {
+    pub move_id: u64,
+    #[serde(rename = "local_language_id")]
+    pub language: u64,
+    pub name: String,
+}
+
+/// Slot-1 and optional slot-2 type of a Pokemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Types {
+    pub first: Type,
+    pub second: Option<Type>,
+}
+
+impl Types {
+    pub fn iter(&self) -> impl Iterator<Item = Type> {
+        std::iter::once(self.first).chain(self.second)
+    }
+}
+
+/// Formats as `Fire` or `Fire/Flying`.
+impl fmt::Display for Types {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.second {
+            Some(second) => f.pad(&format!("{}/{}", self.first, second)),
+            None => f.pad(&self.first.to_string()),
+        }
+    }
+}
+
+/// Pairs the slot-1 and slot-2 types of `(key, slot, type_id)` rows sharing a
+/// key, regardless of row order.
+fn pair_slots<K: Ord + Copy>(
+    rows: impl Iterator<Item = Row<(K, u64, u64)>>,
+    pokemon_id: impl Fn(K) -> u64,
+    diagnostics: &mut Diagnostics,
+) -> Result<BTreeMap<K, Types>> {
+    let mut first_types: BTreeMap<K, Type> = BTreeMap::new();
+    let mut second_types: BTreeMap<K, Row<Type>> = BTreeMap::new();
+
+    for Row {
+        at,
+        value: (key, slot, type_id),
+    } in rows
+    {
+        let Some(type_) = Type::from_id(type_id) else {
+            diagnostics.report(Error::UnknownType { at, type_id })?;
+            continue;
+        };
+
+        match slot {
+            1 => {
+                first_types.insert(key, type_);
+            }
+            2 => {
+                second_types.insert(key, Row { at, value: type_ });
+            }
+            _ => (),
+        }
+    }
+
+    for (key, second_type) in &second_types {
+        if !first_types.contains_key(key) {
+            diagnostics.report(Error::MissingPrimaryType {
+                at: second_type.at.clone(),
+                pokemon_id: pokemon_id(*key),
+            })?;
+        }
+    }
+
+    Ok(first_types
+        .into_iter()
+        .map(|(key, first)| {
+            let types = Types {
+                first,
+                second: second_types.get(&key).map(|row| row.value),
+            };
+            (key, types)
+        })
+        .collect())
+}
+
+/// The PokeAPI CSV files a [`Pokedex`] can load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Dataset {
+    Pokemon,
+    PokemonSpecies,
+    PokemonSpeciesNames,
+    PokemonEvolution,
+    PokemonTypes,
+    PokemonTypesPast,
+    PokemonStats,
+    PokemonAbilities,
+    TypeEfficacy,
+    Moves,
+    MoveNames,
+    PokemonMoves,
+}
+
+/// PokeAPI tables loaded into typed, indexed structures.
+///
+/// Every table is optional: load only the CSVs a consumer needs.
+#[derive(Debug, Default)]
+pub struct Pokedex {
+    pokemon: BTreeMap<u64, Pokemon>,
+    pokemon_by_identifier: HashMap<String, u64>,
+    species_generations: BTreeMap<u64, u64>,
+    /// Previous stage of every species of `pokemon_species.csv`.
+    species_evolves_from: BTreeMap<u64, Option<u64>>,
+    /// Next stages of every species, in id order.
+    species_evolves_into: BTreeMap<u64, Vec<u64>>,
+    evolution_methods: BTreeMap<u64, Vec<EvolutionMethod>>,
+    species_names: BTreeMap<u64, BTreeMap<u64, String>>,
+    /// Pokemon ids of every species in id order.
+    varieties: BTreeMap<u64, Vec<u64>>,
+    pokemon_types: BTreeMap<u64, Types>,
+    /// Former types keyed by the last generation they applied to.
+    pokemon_types_past: BTreeMap<u64, BTreeMap<u64, Types>>,
+    pokemon_by_type: BTreeMap<Type, BTreeSet<u64>>,
+    pokemon_stats: BTreeMap<u64, BaseStats>,
+    /// Abilities of every Pokemon in slot order.
+    pokemon_abilities: BTreeMap<u64, Vec<PokemonAbility>>,
+    type_chart: Option<TypeChart>,
+    moves: BTreeMap<u64, Move>,
+    moves_by_type: BTreeMap<u64, Vec<u64>>,
+    move_names: BTreeMap<u64, BTreeMap<u64, String>>,
+    /// Learnable moves keyed by Pokemon id and version group id.
+    learnsets: BTreeMap<(u64, u64), Vec<LearnableMove>>,
+    sources: BTreeMap<Dataset, SourceInfo>,
+}
+
+impl Pokedex {
+    pub fn new() -> Self {
+        Pokedex::default()
+    }
+
+    pub fn load_pokemon(&mut self, input: &CsvSource, diagnostics: &mut Diagnostics) -> Result<()> {
+        self.sources.insert(Dataset::Pokemon, input.info());
+        for Row { value, .. } in input.rows::<Pokemon>(diagnostics)? {
+            self.pokemon_by_identifier
+                .insert(value.name.clone(), value.id);
+            self.pokemon.insert(value.id, value);
+        }
+        self.index_varieties();
+        Ok(())
+    }
+
+    fn index_varieties(&mut self) {
+        self.varieties.clear();
+        for pokemon in self.pokemon.values() {
+            self.varieties
+                .entry(pokemon.species_id)
+                .or_default()
+                .push(pokemon.id);
+        }
+    }
+
+    pub fn load_pokemon_species(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonSpecies, input.info());
+        for Row { value, .. } in input.rows::<PokemonSpecies>(diagnostics)? {
+            self.species_generations
+                .insert(value.id, value.generation_id);
+            self.species_evolves_from
+                .insert(value.id, value.evolves_from_species_id);
+        }
+        self.index_evolutions();
+        Ok(())
+    }
+
+    fn index_evolutions(&mut self) {
+        self.species_evolves_into.clear();
+        for (species_id, evolves_from) in &self.species_evolves_from {
+            if let Some(evolves_from) = evolves_from {
+                self.species_evolves_into
+                    .entry(*evolves_from)
+                    .or_default()
+                    .push(*species_id);
+            }
+        }
+    }
+
+    /// Loads `pokemon_evolution.csv`, keeping every method of a species in
+    /// file order.
+    pub fn load_pokemon_evolution(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonEvolution, input.info());
+        for Row { at, value } in input.rows::<PokemonEvolution>(diagnostics)? {
+            let Some(trigger) = EvolutionTrigger::from_id(value.evolution_trigger_id) else {
+                diagnostics.report(Error::MalformedRow {
+                    at,
+                    column: Some("evolution_trigger_id".to_owned()),
+                    message: format!("unknown evolution trigger {}", value.evolution_trigger_id),
+                })?;
+                continue;
+            };
+            self.evolution_methods
+                .entry(value.evolved_species_id)
+                .or_default()
+                .push(EvolutionMethod {
+                    trigger,
+                    minimum_level: value.minimum_level,
+                    trigger_item_id: value.trigger_item_id,
+                    held_item_id: value.held_item_id,
+                    known_move_id: value.known_move_id,
+                    minimum_happiness: value.minimum_happiness,
+                    time_of_day: value.time_of_day,
+                    trade_species_id: value.trade_species_id,
+                });
+        }
+        Ok(())
+    }
+
+    pub fn load_pokemon_species_names(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources
+            .insert(Dataset::PokemonSpeciesNames, input.info());
+        for Row { value, .. } in input.rows::<PokemonSpeciesName>(diagnostics)? {
+            self.species_names
+                .entry(value.species_id)
+                .or_default()
+                .entry(value.language)
+                .or_insert(value.name);
+        }
+        Ok(())
+    }
+
+    /// Loads `pokemon_types.csv`, pairing slots regardless of row order.
+    pub fn load_pokemon_types(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonTypes, input.info());
+        let rows = input
+            .rows::<PokemonType>(diagnostics)?
+            .into_iter()
+            .map(|Row { at, value }| Row {
+                at,
+                value: (value.id, value.slot, value.type_id),
+            });
+
+        for (id, types) in pair_slots(rows, |id| id, diagnostics)? {
+            for type_ in types.iter() {
+                self.pokemon_by_type.entry(type_).or_default().insert(id);
+            }
+            self.pokemon_types.insert(id, types);
+        }
+        Ok(())
+    }
+
+    /// Loads `pokemon_types_past.csv`, applied by [`Pokedex::restrict_to_generation`].
+    pub fn load_pokemon_types_past(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonTypesPast, input.info());
+        let rows =
+            input
+                .rows::<PokemonTypePast>(diagnostics)?
+                .into_iter()
+                .map(|Row { at, value }| Row {
+                    at,
+                    value: (
+                        (value.pokemon_id, value.generation_id),
+                        value.slot,
+                        value.type_id,
+                    ),
+                });
+
+        for ((id, generation), types) in pair_slots(rows, |(id, _)| id, diagnostics)? {
+            self.pokemon_types_past
+                .entry(id)
+                .or_default()
+                .insert(generation, types);
+        }
+        Ok(())
+    }
+
+    pub fn load_pokemon_stats(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonStats, input.info());
+        for Row { at, value } in input.rows::<PokemonStat>(diagnostics)? {
+            let stats = self.pokemon_stats.entry(value.pokemon_id).or_default();
+            match stats.get_mut(value.stat_id) {
+                Some(stat) => *stat = value.base_stat,
+                None => diagnostics.report(Error::MalformedRow {
+                    at,
+                    column: Some("stat_id".to_owned()),
+                    message: format!("unknown stat id {}", value.stat_id),
+                })?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_pokemon_abilities(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonAbilities, input.info());
+        for Row { value, .. } in input.rows::<PokemonAbility>(diagnostics)? {
+            self.pokemon_abilities
+                .entry(value.pokemon_id)
+                .or_default()
+                .push(value);
+        }
+        for abilities in self.pokemon_abilities.values_mut() {
+            abilities.sort_by_key(|ability| ability.slot);
+        }
+        Ok(())
+    }
+
+    pub fn load_type_efficacy(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::TypeEfficacy, input.info());
+        self.type_chart = Some(TypeChart::from_csv(input, diagnostics)?);
+        Ok(())
+    }
+
+    pub fn load_moves(&mut self, input: &CsvSource, diagnostics: &mut Diagnostics) -> Result<()> {
+        self.sources.insert(Dataset::Moves, input.info());
+        for Row { value, .. } in input.rows::<Move>(diagnostics)? {
+            self.moves_by_type
+                .entry(value.type_id)
+                .or_default()
+                .push(value.id);
+            self.moves.insert(value.id, value);
+        }
+        Ok(())
+    }
+
+    pub fn load_move_names(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::MoveNames, input.info());
+        for Row { value, .. } in input.rows::<MoveName>(diagnostics)? {
+            // The first name for a language wins, as in the PokeAPI dumps
+            self.move_names
+                .entry(value.move_id)
+                .or_default()
+                .entry(value.language)
+                .or_insert(value.name);
+        }
+        Ok(())
+    }
+
+    /// Loads `pokemon_moves.csv`, sorting every learnset by method, then
+    /// level, then order.
+    pub fn load_pokemon_moves(
+        &mut self,
+        input: &CsvSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        self.sources.insert(Dataset::PokemonMoves, input.info());
+        for Row { at, value } in input.rows::<PokemonMove>(diagnostics)? {
+            let Some(method) = LearnMethod::from_id(value.pokemon_move_method_id) else {
+                diagnostics.report(Error::MalformedRow {
+                    at,
+                    column: Some("pokemon_move_method_id".to_owned()),
+                    message: format!("unknown learn method {}", value.pokemon_move_method_id),
+                })?;
+                continue;
+            };
+            self.learnsets
+                .entry((value.pokemon_id, value.version_group_id))
+                .or_default()
+                .push(LearnableMove {
+                    move_id: value.move_id,
+                    method,
+                    level: value.level,
+                    order: value.order,
+                });
+        }
+        for learnset in self.learnsets.values_mut() {
+            learnset.sort_by_key(|learnable| {
+                (
+                    learnable.method,
+                    learnable.level,
+                    learnable.order,
+                    learnable.move_id,
+                )
+            });
+        }
+        Ok(())
+    }
+
+    /// Name and hash of the file a dataset was loaded from.
+    pub fn source(&self, dataset: Dataset) -> Option<&SourceInfo> {
+        self.sources.get(&dataset)
+    }
+
+    /// Every Pokemon ordered by id.
+    pub fn pokemon(&self) -> impl Iterator<Item = &Pokemon> {
+        self.pokemon.values()
+    }
+
+    /// Ids of the species having at least one Pokemon.
+    pub fn species(&self) -> impl Iterator<Item = u64> + '_ {
+        self.varieties.keys().copied()
+    }
+
+    /// Every Pokemon of a species in id order, the default form included.
+    pub fn varieties(&self, species_id: u64) -> impl Iterator<Item = &Pokemon> {
+        self.varieties
+            .get(&species_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.pokemon.get(id))
+    }
+
+    /// The default form of a species, or its first variety when none is
+    /// flagged as default.
+    pub fn default_form(&self, species_id: u64) -> Option<&Pokemon> {
+        self.varieties(species_id)
+            .find(|pokemon| pokemon.is_default)
+            .or_else(|| self.varieties(species_id).next())
+    }
+
+    /// Ids of the species of `pokemon_species.csv`.
+    pub fn evolution_species(&self) -> impl Iterator<Item = u64> + '_ {
+        self.species_evolves_from.keys().copied()
+    }
+
+    pub fn evolves_from(&self, species_id: u64) -> Option<u64> {
+        self.species_evolves_from
+            .get(&species_id)
+            .copied()
+            .flatten()
+    }
+
+    /// The next stages of a species in id order.
+    pub fn evolves_into(&self, species_id: u64) -> &[u64] {
+        self.species_evolves_into
+            .get(&species_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// How the previous stage evolves into `species_id`.
+    pub fn evolution_methods(&self, species_id: u64) -> &[EvolutionMethod] {
+        self.evolution_methods
+            .get(&species_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The whole chain `species_id` belongs to, from its first stage.
+    pub fn evolution_chain(&self, species_id: u64) -> Option<EvolutionNode> {
+        if !self.species_evolves_from.contains_key(&species_id) {
+            return None;
+        }
+        let mut first = species_id;
+        // Bounded so that a cycle in a broken file cannot hang
+        for _ in 0..self.species_evolves_from.len() {
+            match self.evolves_from(first) {
+                Some(previous) if self.species_evolves_from.contains_key(&previous) => {
+                    first = previous
+                }
+                _ => break,
+            }
+        }
+        Some(self.evolution_node(first, self.species_evolves_from.len()))
+    }
+
+    fn evolution_node(&self, species_id: u64, depth: usize) -> EvolutionNode {
+        let evolves_to = match depth {
+            0 => Vec::new(),
+            _ => self
+                .evolves_into(species_id)
+                .iter()
+                .map(|next| self.evolution_node(*next, depth - 1))
+                .collect(),
+        };
+        EvolutionNode {
+            species_id,
+            methods: self.evolution_methods(species_id).to_vec(),
+            evolves_to,
+        }
+    }
+
+    pub fn pokemon_by_id(&self, id: u64) -> Option<&Pokemon> {
+        self.pokemon.get(&id)
+    }
+
+    pub fn pokemon_by_identifier(&self, identifier: &str) -> Option<&Pokemon> {
+        self.pokemon_by_identifier
+            .get(identifier)
+            .and_then(|id| self.pokemon.get(id))
+    }
+
+    /// The generation a Pokemon's species comes from, using `pokemon_species.csv`
+    /// when loaded and national dex ranges otherwise.
+    ///
+    /// Without `pokemon.csv`, ids are taken as national dex numbers.
+    pub fn generation_of(&self, pokemon_id: u64) -> Option<Generation> {
+        let species_id = self
+            .pokemon
+            .get(&pokemon_id)
+            .map_or(pokemon_id, |pokemon| pokemon.species_id);
+        match self.species_generations.get(&species_id) {
+            Some(generation) => Generation::new(*generation),
+            None => Generation::of_species(species_id),
+        }
+    }
+
+    /// Drops the Pokemon and moves introduced after `generation`, gives
+    /// Pokemon their types of the time from `pokemon_types_past.csv` and
+    /// switches to the type chart of that generation.
+    ///
+    /// Moves keep their current type, e.g. Bite stays Dark in generation 1.
+    /// Call once every table is loaded.
+    pub fn restrict_to_generation(&mut self, generation: Generation) {
+        let introduced = |dex: &Pokedex, id: u64| {
+            dex.generation_of(id)
+                .is_some_and(|introduced| introduced <= generation)
+        };
+
+        let removed: BTreeSet<u64> = self
+            .pokemon
+            .keys()
+            .chain(self.pokemon_types.keys())
+            .chain(self.pokemon_stats.keys())
+            .chain(self.pokemon_abilities.keys())
+            .chain(self.learnsets.keys().map(|(pokemon_id, _)| pokemon_id))
+            .copied()
+            .filter(|id| !introduced(self, *id))
+            .collect();
+        self.pokemon.retain(|id, _| !removed.contains(id));
+        self.index_varieties();
+        let later_species: BTreeSet<u64> = self
+            .species_evolves_from
+            .keys()
+            .copied()
+            .filter(|id| !introduced(self, *id))
+            .collect();
+        self.species_evolves_from
+            .retain(|id, _| !later_species.contains(id));
+        self.evolution_methods
+            .retain(|id, _| !later_species.contains(id));
+        self.index_evolutions();
+        self.pokemon_by_identifier
+            .retain(|_, id| !removed.contains(id));
+        self.pokemon_types.retain(|id, _| !removed.contains(id));
+        self.pokemon_stats.retain(|id, _| !removed.contains(id));
+        self.pokemon_abilities.retain(|id, _| !removed.contains(id));
+
+        for (id, types) in self.pokemon_types.iter_mut() {
+            // The earliest change still in effect in `generation`
+            let past = self
+                .pokemon_types_past
+                .get(id)
+                .and_then(|past| past.range(generation.number()..).next());
+            if let Some((_, past_types)) = past {
+                *types = *past_types;
+            }
+        }
+        self.pokemon_by_type.clear();
+        for (id, types) in &self.pokemon_types {
+            for type_ in types.iter() {
+                self.pokemon_by_type.entry(type_).or_default().insert(*id);
+            }
+        }
+
+        self.moves
+            .retain(|_, move_| move_.generation_id <= generation.number());
+        if self.sources.contains_key(&Dataset::Moves) {
+            let moves = &self.moves;
+            for ids in self.moves_by_type.values_mut() {
+                ids.retain(|id| moves.contains_key(id));
+            }
+            self.moves_by_type.retain(|_, ids| !ids.is_empty());
+            self.move_names.retain(|id, _| moves.contains_key(id));
+            for learnset in self.learnsets.values_mut() {
+                learnset.retain(|learnable| moves.contains_key(&learnable.move_id));
+            }
+        }
+        self.learnsets
+            .retain(|(pokemon_id, _), _| !removed.contains(pokemon_id));
+
+        self.type_chart = Some(generation.type_chart(self.type_chart()));
+    }
+
+    /// Types of every Pokemon ordered by Pokemon id.
+    pub fn pokemon_types(&self) -> impl Iterator<Item = (u64, Types)> + '_ {
+        self.pokemon_types.iter().map(|(id, types)| (*id, *types))
+    }
+
+    pub fn types_of(&self, pokemon_id: u64) -> Option<Types> {
+        self.pokemon_types.get(&pokemon_id).copied()
+    }
+
+    /// Ids of the Pokemon having `type_` in either slot.
+    pub fn pokemon_with_type(&self, type_: Type) -> impl Iterator<Item = u64> + '_ {
+        self.pokemon_by_type
+            .get(&type_)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Base stats from `pokemon_stats.csv`; stats missing from the file are 0.
+    pub fn base_stats(&self, pokemon_id: u64) -> Option<BaseStats> {
+        self.pokemon_stats.get(&pokemon_id).copied()
+    }
+
+    /// Base stats of every Pokemon ordered by Pokemon id.
+    pub fn pokemon_stats(&self) -> impl Iterator<Item = (u64, BaseStats)> + '_ {
+        self.pokemon_stats.iter().map(|(id, stats)| (*id, *stats))
+    }
+
+    /// Abilities of a Pokemon in slot order, the hidden one included.
+    pub fn abilities_of(&self, pokemon_id: u64) -> &[PokemonAbility] {
+        self.pokemon_abilities
+            .get(&pokemon_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Abilities of every Pokemon ordered by Pokemon id.
+    pub fn pokemon_abilities(&self) -> impl Iterator<Item = (u64, &[PokemonAbility])> {
+        self.pokemon_abilities
+            .iter()
+            .map(|(id, abilities)| (*id, abilities.as_slice()))
+    }
+
+    /// The chart loaded from `type_efficacy.csv`, or the standard one.
+    pub fn type_chart(&self) -> &TypeChart {
+        self.type_chart.as_ref().unwrap_or(TypeChart::standard())
+    }
+
+    /// Every move ordered by id.
+    pub fn moves(&self) -> impl Iterator<Item = &Move> {
+        self.moves.values()
+    }
+
+    /// Moves matching `filter`, ordered by id.
+    pub fn query_moves<'a>(&'a self, filter: &'a MoveFilter) -> impl Iterator<Item = &'a Move> {
+        self.moves.values().filter(|move_| filter.matches(move_))
+    }
+
+    pub fn move_by_id(&self, id: u64) -> Option<&Move> {
+        self.moves.get(&id)
+    }
+
+    /// Type of a move, `None` for unknown moves and moves of the unofficial
+    /// types such as Shadow.
+    pub fn move_type(&self, move_id: u64) -> Option<Type> {
+        self.move_by_id(move_id)
+            .and_then(|move_| Type::from_id(move_.type_id))
+    }
+
+    /// Move ids of the given type, in file order.
+    pub fn moves_with_type(&self, type_id: u64) -> &[u64] {
+        self.moves_by_type.get(&type_id).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn move_name(&self, move_id: u64, language: u64) -> Option<&str> {
+        self.move_names
+            .get(&move_id)
+            .and_then(|names| names.get(&language))
+            .map(String::as_str)
+    }
+
+    /// The name of a move in the first language of `languages` having one.
+    pub fn localized_move_name(&self, move_id: u64, languages: &[Language]) -> Option<&str> {
+        languages
+            .iter()
+            .find_map(|language| self.move_name(move_id, language.id()))
+    }
+
+    /// Ids of the moves having a name in any language.
+    pub fn named_moves(&self) -> impl Iterator<Item = u64> + '_ {
+        self.move_names.keys().copied()
+    }
+
+    /// The species name of a Pokemon in the first language of `languages`
+    /// having one.
+    ///
+    /// Without `pokemon.csv`, ids are taken as species ids.
+    pub fn localized_pokemon_name(&self, pokemon_id: u64, languages: &[Language]) -> Option<&str> {
+        let species_id = self
+            .pokemon
+            .get(&pokemon_id)
+            .map_or(pokemon_id, |pokemon| pokemon.species_id);
+        let names = self.species_names.get(&species_id)?;
+        languages
+            .iter()
+            .find_map(|language| names.get(&language.id()))
+            .map(String::as_str)
+    }
+
+    /// Version groups having at least one learnset, in id order.
+    pub fn version_groups(&self) -> BTreeSet<u64> {
+        self.learnsets
+            .keys()
+            .map(|(_, version_group_id)| *version_group_id)
+            .collect()
+    }
+
+    /// The moves a Pokemon learns in a version group, level-up moves first
+    /// by level.
+    pub fn learnset(&self, pokemon_id: u64, version_group_id: u64) -> &[LearnableMove] {
+        self.learnsets
+            .get(&(pokemon_id, version_group_id))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Every learnset of a version group, ordered by Pokemon id.
+    pub fn learnsets(
+        &self,
+        version_group_id: u64,
+    ) -> impl Iterator<Item = (u64, &[LearnableMove])> {
+        self.learnsets
+            .iter()
+            .filter(move |((_, version_group), _)| *version_group == version_group_id)
+            .map(|((pokemon_id, _), learnset)| (*pokemon_id, learnset.as_slice()))
+    }
+
+    /// Move names in one language ordered by move id.
+    pub fn move_names(&self, language: u64) -> impl Iterator<Item = (u64, &str)> + '_ {
+        self.move_names
+            .iter()
+            .filter_map(move |(id, names)| names.get(&language).map(|name| (*id, name.as_str())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OnBadRow;
+    use crate::types::Effectiveness;
+
+    fn load_types(input: &str, diagnostics: &mut Diagnostics) -> Result<Pokedex> {
+        let mut dex = Pokedex::new();
+        dex.load_pokemon_types(&CsvSource::new("pokemon_types.csv", input), diagnostics)?;
+        Ok(dex)
+    }
+
+    #[test]
+    fn test_pokemon_indexes() {
+        let mut input = String::new();
+        input.push_str("id,identifier,species_id,height,weight,base_experience,order,is_default\n");
+        input.push_str("1,bulbasaur,1,7,69,64,1,1\n");
+        input.push_str("25,pikachu,25,4,60,112,35,1\n");
+
+        let mut dex = Pokedex::new();
+        dex.load_pokemon(
+            &CsvSource::new("pokemon.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(dex.pokemon_by_identifier("pikachu").unwrap().id, 25);
+        assert_eq!(dex.pokemon_by_id(1).unwrap().name, "bulbasaur");
+        assert!(dex.pokemon_by_identifier("mew").is_none());
+        assert_eq!(dex.pokemon().count(), 2);
+    }
+
+    #[test]
+    fn test_pokemon_types_by_type() {
+        let input = "pokemon_id,type_id,slot\n1,4,2\n1,12,1\n43,12,1\n43,4,2\n25,13,1\n";
+
+        let dex = load_types(input, &mut Diagnostics::default()).unwrap();
+
+        assert_eq!(
+            dex.types_of(1),
+            Some(Types {
+                first: Type::Grass,
+                second: Some(Type::Poison)
+            })
+        );
+        assert_eq!(
+            dex.pokemon_with_type(Type::Poison).collect::<Vec<_>>(),
+            vec![1, 43]
+        );
+        assert_eq!(
+            dex.pokemon_with_type(Type::Electric).collect::<Vec<_>>(),
+            vec![25]
+        );
+        assert_eq!(dex.pokemon_with_type(Type::Fire).count(), 0);
+    }
+
+    #[test]
+    fn test_pokemon_types_errors() {
+        let input = "pokemon_id,type_id,slot\n1,12,1\n2,4,2\n3,19,1\n";
+
+        let err = load_types(input, &mut Diagnostics::default()).unwrap_err();
+        assert!(matches!(err, Error::UnknownType { type_id: 19, .. }));
+        assert_eq!(err.location().unwrap().line, 4);
+
+        let mut diagnostics = Diagnostics::new(OnBadRow::SkipAndWarn);
+        let dex = load_types(input, &mut diagnostics).unwrap();
+        assert_eq!(dex.pokemon_types().count(), 1);
+        assert!(matches!(
+            diagnostics.warnings(),
+            [
+                Error::UnknownType { .. },
+                Error::MissingPrimaryType { pokemon_id: 2, .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_pokemon_stats() {
+        let mut input = String::from("pokemon_id,stat_id,base_stat,effort\n");
+        for (stat_id, base_stat) in [45, 49, 49, 65, 65, 45].iter().enumerate() {
+            input.push_str(&format!("1,{},{base_stat},0\n", stat_id + 1));
+        }
+        input.push_str("25,6,90,2\n25,9,1,0\n");
+        let source = CsvSource::new("pokemon_stats.csv", input);
+
+        let err = Pokedex::new()
+            .load_pokemon_stats(&source, &mut Diagnostics::default())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("pokemon_stats.csv:9: malformed row, column `stat_id`"));
+
+        let mut dex = Pokedex::new();
+        dex.load_pokemon_stats(&source, &mut Diagnostics::new(OnBadRow::SkipAndWarn))
+            .unwrap();
+        let bulbasaur = dex.base_stats(1).unwrap();
+        assert_eq!(bulbasaur.special_attack, 65);
+        assert_eq!(bulbasaur.total(), 318);
+        assert_eq!(dex.base_stats(25).unwrap().speed, 90);
+        assert_eq!(dex.base_stats(25).unwrap().hp, 0);
+        assert_eq!(dex.base_stats(4), None);
+    }
+
+    #[test]
+    fn test_pokemon_abilities() {
+        let input = "pokemon_id,ability_id,is_hidden,slot\n\
+                     1,34,1,3\n\
+                     1,65,0,1\n\
+                     132,7,0,1\n\
+                     132,150,1,3\n";
+        let mut dex = bundled_dex();
+        dex.load_pokemon_abilities(
+            &CsvSource::new("pokemon_abilities.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        let bulbasaur = dex.abilities_of(1);
+        assert_eq!(bulbasaur.len(), 2);
+        assert_eq!(
+            (bulbasaur[0].ability_id, bulbasaur[0].is_hidden),
+            (65, false)
+        );
+        assert_eq!(
+            (bulbasaur[1].ability_id, bulbasaur[1].is_hidden),
+            (34, true)
+        );
+        assert!(dex.abilities_of(4).is_empty());
+        assert_eq!(dex.pokemon_abilities().count(), 2);
+
+        let pokemon = dex.pokemon_by_id(1).unwrap();
+        assert_eq!((pokemon.height, pokemon.weight), (7, 69));
+        assert_eq!(pokemon.base_experience, Some(64));
+        assert_eq!(dex.pokemon_by_id(905).unwrap().base_experience, None);
+    }
+
+    #[test]
+    fn test_moves_by_type() {
+        let mut input = String::new();
+        input.push_str("id,identifier,generation_id,type_id,power,pp,accuracy,priority,target_id,damage_class_id,effect_id,effect_chance,contest_type_id,contest_effect_id,super_contest_effect_id\n");
+        input.push_str("1,pound,1,1,40,35,100,0,10,2,1,,5,1,5\n");
+        input.push_str("14,swords-dance,1,1,,20,,0,7,1,51,,1,11,21\n");
+
+        let mut dex = Pokedex::new();
+        dex.load_moves(
+            &CsvSource::new("moves.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(dex.moves_with_type(1), &[1, 14]);
+        assert_eq!(dex.move_by_id(14).unwrap().power, None);
+        assert_eq!(dex.move_by_id(14).unwrap().accuracy, None);
+        assert_eq!(
+            dex.move_by_id(14).unwrap().damage_class(),
+            Some(DamageClass::Status)
+        );
+        assert_eq!(
+            dex.move_by_id(1).unwrap().damage_class(),
+            Some(DamageClass::Physical)
+        );
+        assert!(dex.moves_with_type(2).is_empty());
+    }
+
+    #[test]
+    fn test_query_moves() {
+        let mut dex = Pokedex::new();
+        dex.load_moves(
+            &CsvSource::new("moves.csv", include_str!("../csv/moves.csv")),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+        let ids = |filter: &MoveFilter| -> Vec<u64> {
+            dex.query_moves(filter).map(|move_| move_.id).collect()
+        };
+
+        let filter = MoveFilter {
+            damage_class: Some(DamageClass::Physical),
+            max_generation: Some(4),
+            min_accuracy: Some(90),
+            ..MoveFilter::damaging()
+        };
+        let physical = ids(&filter);
+        for id in [1, 157, 370, 416] {
+            assert!(physical.contains(&id), "move {id} should match");
+        }
+        // Stone Edge misses too often, Wild Charge is from generation 5
+        assert!(!physical.contains(&444) && !physical.contains(&528));
+        assert!(dex
+            .query_moves(&filter)
+            .all(|move_| move_.power.is_some() && move_.accuracy.unwrap_or(100) >= 90));
+
+        let filter = MoveFilter {
+            min_priority: Some(1),
+            max_priority: Some(2),
+            max_generation: Some(2),
+            ..MoveFilter::damaging()
+        };
+        assert_eq!(ids(&filter), [98, 183, 245]);
+
+        assert_eq!(
+            dex.query_moves(&MoveFilter::default()).count(),
+            dex.moves().count()
+        );
+        let swords_dance = dex.move_by_id(14).unwrap();
+        assert_eq!(swords_dance.identifier, "swords-dance");
+        assert_eq!((swords_dance.pp, swords_dance.target_id), (Some(20), 7));
+        assert!(!MoveFilter::damaging().matches(swords_dance));
+    }
+
+    #[test]
+    fn test_varieties() {
+        let mut input = String::new();
+        input.push_str("id,identifier,species_id,height,weight,base_experience,order,is_default\n");
+        input.push_str("26,raichu,26,8,300,243,35,1\n");
+        input.push_str("10100,raichu-alola,26,7,210,243,36,0\n");
+        input.push_str("25,pikachu,25,4,60,112,32,1\n");
+        input.push_str("10080,pikachu-rock-star,25,4,60,112,,0\n");
+        input.push_str("10001,deoxys-attack,386,17,608,270,,0\n");
+        let mut dex = Pokedex::new();
+        let mut diagnostics = Diagnostics::default();
+        dex.load_pokemon(&CsvSource::new("pokemon.csv", input), &mut diagnostics)
+            .unwrap();
+
+        assert_eq!(dex.species().collect::<Vec<_>>(), [25, 26, 386]);
+        let ids = |species_id| -> Vec<u64> {
+            dex.varieties(species_id)
+                .map(|pokemon| pokemon.id)
+                .collect()
+        };
+        assert_eq!(ids(26), [26, 10100]);
+        assert_eq!(ids(1), []);
+        assert!(!dex.pokemon_by_id(10100).unwrap().is_default);
+        assert_eq!(dex.default_form(25).unwrap().name, "pikachu");
+        // No default in the file, the first variety stands in
+        assert_eq!(dex.default_form(386).unwrap().id, 10001);
+        assert!(dex.default_form(1).is_none());
+
+        let bad = "id,identifier,species_id,height,weight,base_experience,order,is_default\n\
+                   1,bulbasaur,1,7,69,64,1,yes\n";
+        let err = Pokedex::new()
+            .load_pokemon(&CsvSource::new("pokemon.csv", bad), &mut diagnostics)
+            .unwrap_err();
+        assert!(err.to_string().contains("is_default"));
+    }
+
+    #[test]
+    fn test_learnsets() {
+        let input = "pokemon_id,version_group_id,move_id,pokemon_move_method_id,level,order\n\
+                     1,1,33,1,1,\n\
+                     1,1,22,1,13,\n\
+                     1,1,45,1,1,\n\
+                     1,1,15,4,0,\n\
+                     1,1,73,1,7,\n\
+                     1,18,22,1,9,\n\
+                     1,18,300,2,0,\n\
+                     152,18,33,1,1,\n";
+        let mut dex = bundled_dex();
+        dex.load_pokemon_moves(
+            &CsvSource::new("pokemon_moves.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        let red_blue: Vec<(u64, LearnMethod, u64)> = dex
+            .learnset(1, 1)
+            .iter()
+            .map(|learnable| (learnable.move_id, learnable.method, learnable.level))
+            .collect();
+        assert_eq!(
+            red_blue,
+            [
+                (33, LearnMethod::LevelUp, 1),
+                (45, LearnMethod::LevelUp, 1),
+                (73, LearnMethod::LevelUp, 7),
+                (22, LearnMethod::LevelUp, 13),
+                (15, LearnMethod::Machine, 0),
+            ]
+        );
+        assert_eq!(dex.learnset(1, 18)[0].level, 9);
+        assert!(dex.learnset(1, 2).is_empty());
+        assert_eq!(
+            dex.version_groups().into_iter().collect::<Vec<_>>(),
+            [1, 18]
+        );
+        let pokemon: Vec<u64> = dex.learnsets(18).map(|(id, _)| id).collect();
+        assert_eq!(pokemon, [1, 152]);
+
+        // Chikorita and Mud Sport came later
+        dex.restrict_to_generation(Generation::new(1).unwrap());
+        assert_eq!(dex.learnsets(18).count(), 1);
+        assert_eq!(dex.learnset(1, 18).len(), 1);
+
+        let bad = "pokemon_id,version_group_id,move_id,pokemon_move_method_id,level,order\n\
+                   1,1,33,12,1,\n";
+        let err = Pokedex::new()
+            .load_pokemon_moves(
+                &CsvSource::new("pokemon_moves.csv", bad),
+                &mut Diagnostics::default(),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("pokemon_move_method_id"));
+        assert_eq!("Machine".parse(), Ok(LearnMethod::Machine));
+        assert!("hm".parse::<LearnMethod>().is_err());
+    }
+
+    /// Bulbasaur's line and Eevee with two of its branches.
+    fn evolution_dex() -> Pokedex {
+        let mut dex = Pokedex::new();
+        let mut diagnostics = Diagnostics::default();
+        let species = "id,identifier,generation_id,evolves_from_species_id,evolution_chain_id\n\
+                       1,bulbasaur,1,,1\n\
+                       2,ivysaur,1,1,1\n\
+                       3,venusaur,1,2,1\n\
+                       133,eevee,1,,67\n\
+                       134,vaporeon,1,133,67\n\
+                       197,umbreon,2,133,67\n\
+                       132,ditto,1,,66\n";
+        dex.load_pokemon_species(
+            &CsvSource::new("pokemon_species.csv", species),
+            &mut diagnostics,
+        )
+        .unwrap();
+        let evolution = "id,evolved_species_id,evolution_trigger_id,trigger_item_id,minimum_level,\
+                         gender_id,location_id,held_item_id,time_of_day,known_move_id,\
+                         known_move_type_id,minimum_happiness,minimum_beauty,minimum_affection,\
+                         relative_physical_stats,party_species_id,party_type_id,trade_species_id,\
+                         needs_overworld_rain,turn_upside_down\n\
+                         1,2,1,,16,,,,,,,,,,,,,,0,0\n\
+                         2,3,1,,32,,,,,,,,,,,,,,0,0\n\
+                         3,134,3,84,,,,,,,,,,,,,,,0,0\n\
+                         4,197,1,,,,,,night,,,160,,,,,,,0,0\n";
+        dex.load_pokemon_evolution(
+            &CsvSource::new("pokemon_evolution.csv", evolution),
+            &mut diagnostics,
+        )
+        .unwrap();
+        dex
+    }
+
+    #[test]
+    fn test_evolution_chain() {
+        let dex = evolution_dex();
+
+        let chain = dex.evolution_chain(3).unwrap();
+        assert_eq!(chain, dex.evolution_chain(1).unwrap());
+        assert_eq!(chain.species(), [1, 2, 3]);
+        assert!(chain.methods.is_empty());
+        let venusaur = chain.find(3).unwrap();
+        assert_eq!(venusaur.methods[0].trigger, EvolutionTrigger::LevelUp);
+        assert_eq!(venusaur.methods[0].minimum_level, Some(32));
+
+        let eevee = dex.evolution_chain(197).unwrap();
+        assert_eq!(eevee.species(), [133, 134, 197]);
+        let vaporeon = &eevee.evolves_to[0].methods[0];
+        assert_eq!(vaporeon.trigger, EvolutionTrigger::UseItem);
+        assert_eq!(vaporeon.trigger_item_id, Some(84));
+        assert_eq!(vaporeon.time_of_day, None);
+        let umbreon = &eevee.evolves_to[1].methods[0];
+        assert_eq!(umbreon.time_of_day.as_deref(), Some("night"));
+        assert_eq!(umbreon.minimum_happiness, Some(160));
+
+        let ditto = dex.evolution_chain(132).unwrap();
+        assert!(ditto.evolves_to.is_empty());
+        assert_eq!(dex.evolution_chain(25), None);
+        assert_eq!(dex.evolves_from(2), Some(1));
+        assert_eq!(dex.evolves_into(133), [134, 197]);
+    }
+
+    #[test]
+    fn test_evolution_generation() {
+        let mut dex = evolution_dex();
+        dex.restrict_to_generation(Generation::new(1).unwrap());
+
+        assert_eq!(dex.evolution_chain(133).unwrap().species(), [133, 134]);
+        assert_eq!(dex.evolution_chain(197), None);
+        assert!(dex.evolution_methods(197).is_empty());
+    }
+
+    #[test]
+    fn test_evolution_errors() {
+        let input = "id,evolved_species_id,evolution_trigger_id\n1,2,99\n";
+        let err = Pokedex::new()
+            .load_pokemon_evolution(
+                &CsvSource::new("pokemon_evolution.csv", input),
+                &mut Diagnostics::default(),
+            )
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("evolution_trigger_id"));
+        assert!(err.contains("unknown evolution trigger 99"));
+    }
+
+    /// The bundled CSV files, with a few `pokemon_types_past.csv` rows.
+    fn bundled_dex() -> Pokedex {
+        let mut dex = Pokedex::new();
+        let mut diagnostics = Diagnostics::default();
+        dex.load_pokemon(
+            &CsvSource::new("pokemon.csv", include_str!("../csv/pokemon.csv")),
+            &mut diagnostics,
+        )
+        .unwrap();
+        dex.load_pokemon_types(
+            &CsvSource::new(
+                "pokemon_types.csv",
+                include_str!("../csv/pokemon_types.csv"),
+            ),
+            &mut diagnostics,
+        )
+        .unwrap();
+        dex.load_moves(
+            &CsvSource::new("moves.csv", include_str!("../csv/moves.csv")),
+            &mut diagnostics,
+        )
+        .unwrap();
+        // Clefairy was Normal up to generation 5, Magnemite pure Electric in generation 1
+        let past = "pokemon_id,generation_id,type_id,slot\n35,5,1,1\n81,1,13,1\n";
+        dex.load_pokemon_types_past(
+            &CsvSource::new("pokemon_types_past.csv", past),
+            &mut diagnostics,
+        )
+        .unwrap();
+        dex
+    }
+
+    #[test]
+    fn test_generation_1() {
+        let mut dex = bundled_dex();
+        dex.restrict_to_generation(Generation::new(1).unwrap());
+
+        assert_eq!(dex.pokemon().count(), 151);
+        assert!(dex.pokemon_by_identifier("chikorita").is_none());
+        assert_eq!(dex.pokemon_types().count(), 151);
+        assert!(dex.moves().all(|move_| move_.generation_id == 1));
+        assert_eq!(dex.moves().count(), 165);
+
+        let magnemite = dex.types_of(81).unwrap();
+        assert_eq!((magnemite.first, magnemite.second), (Type::Electric, None));
+        assert_eq!(dex.types_of(35).unwrap().first, Type::Normal);
+        assert!(!dex.type_chart().contains(Type::Steel));
+        assert_eq!(
+            dex.type_chart().factor(Type::Ghost, Type::Psychic),
+            Some(Effectiveness::IMMUNE)
+        );
+    }
+
+    #[test]
+    fn test_generation_3() {
+        let mut dex = bundled_dex();
+        dex.restrict_to_generation(Generation::new(3).unwrap());
+
+        assert_eq!(dex.pokemon().count(), 386);
+        assert!(dex.pokemon_by_identifier("rayquaza").is_some());
+        assert!(
+            dex.move_by_id(370).is_none(),
+            "close-combat is from generation 4"
+        );
+        assert_eq!(dex.types_of(35).unwrap().first, Type::Normal);
+        assert_eq!(dex.types_of(81).unwrap().second, Some(Type::Steel));
+        assert!(!dex.pokemon_with_type(Type::Fairy).any(|id| id == 35));
+        assert_eq!(
+            dex.type_chart().factor(Type::Dark, Type::Steel),
+            Some(Effectiveness::HALF)
+        );
+    }
+
+    #[test]
+    fn test_generation_6() {
+        let mut dex = bundled_dex();
+        dex.restrict_to_generation(Generation::new(6).unwrap());
+
+        assert_eq!(dex.pokemon().count(), 721);
+        assert_eq!(dex.types_of(35).unwrap().first, Type::Fairy);
+        assert_eq!(dex.type_chart(), TypeChart::standard());
+    }
+
+    #[test]
+    fn test_species_generation() {
+        let mut dex = Pokedex::new();
+        let input = "id,identifier,generation_id,evolves_from_species_id\n\
+                     1,bulbasaur,1,\n\
+                     152,chikorita,4,\n";
+        dex.load_pokemon_species(
+            &CsvSource::new("pokemon_species.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(dex.generation_of(1), Generation::new(1));
+        // The species table wins over national dex ranges
+        assert_eq!(dex.generation_of(152), Generation::new(4));
+        assert_eq!(dex.generation_of(252), Generation::new(3));
+        assert_eq!(dex.generation_of(10_001), None);
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let mut dex = bundled_dex();
+        let mut diagnostics = Diagnostics::default();
+        dex.load_move_names(
+            &CsvSource::new("move_names.csv", include_str!("../csv/move_names.csv")),
+            &mut diagnostics,
+        )
+        .unwrap();
+        let input = "pokemon_species_id,local_language_id,name,genus\n\
+                     25,9,Pikachu,Mouse Pok\u{e9}mon\n\
+                     25,11,\u{30d4}\u{30ab}\u{30c1}\u{30e5}\u{30a6},\n\
+                     122,5,M. Mime,\n";
+        dex.load_pokemon_species_names(
+            &CsvSource::new("pokemon_species_names.csv", input),
+            &mut diagnostics,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dex.localized_move_name(1, &[Language::It, Language::En]),
+            Some("Botta")
+        );
+        assert_eq!(dex.localized_move_name(1, &[Language::En]), Some("Pound"));
+        assert_eq!(dex.localized_move_name(100_000, &[Language::En]), None);
+
+        let chain = [Language::It, Language::Ja, Language::En];
+        assert_eq!(dex.localized_pokemon_name(25, &chain), Some("ピカチュウ"));
+        assert_eq!(dex.localized_pokemon_name(122, &chain), None);
+        assert_eq!(
+            dex.localized_pokemon_name(122, &[Language::Fr]),
+            Some("M. Mime")
+        );
+    }
+}
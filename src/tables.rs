@@ -0,0 +1,420 @@
+//! Tables exported from a [`Pokedex`], shared by every output format.
+
+use crate::display_name::display_name;
+use crate::emit::{Table, Value};
+use crate::evolution::EvolutionMethod;
+use crate::language::Language;
+use crate::pokedex::{Dataset, LearnMethod, MoveFilter, Pokedex, PokemonAbility};
+use crate::types::Type;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Written in place of the missing slot-2 type.
+const EMPTY_TYPE: u64 = 0;
+
+fn int(value: u64) -> Value {
+    Value::Int(value as i64)
+}
+
+fn ints(values: impl IntoIterator<Item = u64>) -> Value {
+    Value::List(values.into_iter().map(int).collect())
+}
+
+/// An empty table carrying the sources of `datasets`.
+fn table(name: &str, dex: &Pokedex, datasets: &[Dataset]) -> Table {
+    let mut table = Table::new(name);
+    table.sources = datasets
+        .iter()
+        .filter_map(|dataset| dex.source(*dataset).cloned())
+        .collect();
+    table
+}
+
+/// How tables keyed by Pokemon id treat alternate forms such as megas and
+/// regional variants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormLayout {
+    /// Only the default form of each species, keyed by species id.
+    Species,
+    /// Every Pokemon keyed by its own id.
+    #[default]
+    AllForms,
+    /// One `{ default_form = id, forms = { [id] = ... } }` record per species id.
+    Nested,
+}
+
+impl FormLayout {
+    pub const ALL: [FormLayout; 3] = [
+        FormLayout::Species,
+        FormLayout::AllForms,
+        FormLayout::Nested,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FormLayout::Species => "species",
+            FormLayout::AllForms => "all",
+            FormLayout::Nested => "nested",
+        }
+    }
+}
+
+impl std::str::FromStr for FormLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FormLayout::ALL
+            .into_iter()
+            .find(|layout| layout.name() == s)
+            .ok_or_else(|| format!("unknown form layout `{s}`"))
+    }
+}
+
+/// Rearranges a table keyed by Pokemon id according to `layout`.
+///
+/// Outside [`FormLayout::AllForms`], entries of Pokemon missing from
+/// `pokemon.csv` are dropped since their species is unknown.
+pub fn with_forms(table: Table, dex: &Pokedex, layout: FormLayout) -> Table {
+    if layout == FormLayout::AllForms {
+        return table;
+    }
+
+    let mut entries = table.entries;
+    let mut arranged = Table {
+        entries: Default::default(),
+        ..table
+    };
+    if let Some(source) = dex.source(Dataset::Pokemon) {
+        if !arranged.sources.contains(source) {
+            arranged.sources.push(source.clone());
+        }
+    }
+    for species_id in dex.species() {
+        let Some(default_form) = dex.default_form(species_id) else {
+            continue;
+        };
+        match layout {
+            FormLayout::Species => {
+                if let Some(value) = entries.remove(&default_form.id) {
+                    arranged.insert(species_id, value);
+                }
+            }
+            FormLayout::Nested => {
+                let forms: BTreeMap<u64, Value> = dex
+                    .varieties(species_id)
+                    .filter_map(|pokemon| Some((pokemon.id, entries.remove(&pokemon.id)?)))
+                    .collect();
+                if !forms.is_empty() {
+                    let record = vec![
+                        ("default_form".to_owned(), int(default_form.id)),
+                        ("forms".to_owned(), Value::Map(forms)),
+                    ];
+                    arranged.insert(species_id, Value::Record(record));
+                }
+            }
+            FormLayout::AllForms => unreachable!(),
+        }
+    }
+    arranged
+}
+
+/// `pokemon_names_it` for a chain starting with Italian.
+fn localized_name(name: &str, languages: &[Language]) -> String {
+    match languages.first() {
+        Some(language) => format!("{name}_{}", language.suffix()),
+        None => name.to_owned(),
+    }
+}
+
+pub fn pokemon_names(dex: &Pokedex) -> Table {
+    let mut table = table("pokemon_names", dex, &[Dataset::Pokemon]);
+    for pokemon in dex.pokemon() {
+        table.insert(pokemon.id, Value::Str(display_name(&pokemon.name)));
+    }
+    table
+}
+
+/// Species names in the first language of `languages` having a translation,
+/// falling back to the English display name of the identifier.
+pub fn localized_pokemon_names(dex: &Pokedex, languages: &[Language]) -> Table {
+    let mut table = table(
+        &localized_name("pokemon_names", languages),
+        dex,
+        &[Dataset::Pokemon, Dataset::PokemonSpeciesNames],
+    );
+    for pokemon in dex.pokemon() {
+        let name = match dex.localized_pokemon_name(pokemon.id, languages) {
+            Some(name) => name.to_owned(),
+            None => display_name(&pokemon.name),
+        };
+        table.insert(pokemon.id, Value::Str(name));
+    }
+    table
+}
+
+/// Previous stage and next stages of every species of `pokemon_species.csv`.
+///
+/// Each next stage is listed once per evolution method; absent ids and
+/// levels are 0, an absent trigger or time of day is empty.
+pub fn evolutions(dex: &Pokedex) -> Table {
+    let mut table = table(
+        "evolutions",
+        dex,
+        &[Dataset::PokemonSpecies, Dataset::PokemonEvolution],
+    );
+    for species_id in dex.evolution_species() {
+        let mut evolves_to = Vec::new();
+        for next in dex.evolves_into(species_id) {
+            let methods = dex.evolution_methods(*next);
+            if methods.is_empty() {
+                evolves_to.push(evolution(*next, None));
+            }
+            evolves_to.extend(methods.iter().map(|method| evolution(*next, Some(method))));
+        }
+        let record = vec![
+            (
+                "evolves_from".to_owned(),
+                int(dex.evolves_from(species_id).unwrap_or(0)),
+            ),
+            ("evolves_to".to_owned(), Value::List(evolves_to)),
+        ];
+        table.insert(species_id, Value::Record(record));
+    }
+    table
+}
+
+fn evolution(species_id: u64, method: Option<&EvolutionMethod>) -> Value {
+    let id = |field: fn(&EvolutionMethod) -> Option<u64>| int(method.and_then(field).unwrap_or(0));
+    Value::Record(vec![
+        ("species_id".to_owned(), int(species_id)),
+        (
+            "trigger".to_owned(),
+            Value::Str(
+                method
+                    .map_or("", |method| method.trigger.identifier())
+                    .to_owned(),
+            ),
+        ),
+        (
+            "minimum_level".to_owned(),
+            id(|method| method.minimum_level),
+        ),
+        (
+            "trigger_item_id".to_owned(),
+            id(|method| method.trigger_item_id),
+        ),
+        ("held_item_id".to_owned(), id(|method| method.held_item_id)),
+        (
+            "known_move_id".to_owned(),
+            id(|method| method.known_move_id),
+        ),
+        (
+            "minimum_happiness".to_owned(),
+            id(|method| method.minimum_happiness),
+        ),
+        (
+            "time_of_day".to_owned(),
+            Value::Str(
+                method
+                    .and_then(|method| method.time_of_day.clone())
+                    .unwrap_or_default(),
+            ),
+        ),
+        (
+            "trade_species_id".to_owned(),
+            id(|method| method.trade_species_id),
+        ),
+    ])
+}
+
+/// Height in decimetres, weight in hectograms and base experience, 0 when
+/// PokeAPI has none.
+pub fn pokemon_attributes(dex: &Pokedex) -> Table {
+    let mut table = table("pokemon_attributes", dex, &[Dataset::Pokemon]);
+    for pokemon in dex.pokemon() {
+        let record = vec![
+            ("height".to_owned(), int(pokemon.height)),
+            ("weight".to_owned(), int(pokemon.weight)),
+            (
+                "base_experience".to_owned(),
+                int(pokemon.base_experience.unwrap_or(0)),
+            ),
+        ];
+        table.insert(pokemon.id, Value::Record(record));
+    }
+    table
+}
+
+pub fn pokemon_stats(dex: &Pokedex) -> Table {
+    let mut table = table("pokemon_stats", dex, &[Dataset::PokemonStats]);
+    for (id, stats) in dex.pokemon_stats() {
+        let record = [
+            ("hp", stats.hp),
+            ("attack", stats.attack),
+            ("defense", stats.defense),
+            ("special_attack", stats.special_attack),
+            ("special_defense", stats.special_defense),
+            ("speed", stats.speed),
+        ]
+        .into_iter()
+        .map(|(name, stat)| (name.to_owned(), int(stat)))
+        .collect();
+        table.insert(id, Value::Record(record));
+    }
+    table
+}
+
+/// Regular ability ids in slot order and the hidden ability id, 0 for none.
+pub fn pokemon_abilities(dex: &Pokedex) -> Table {
+    let mut table = table("pokemon_abilities", dex, &[Dataset::PokemonAbilities]);
+    for (id, abilities) in dex.pokemon_abilities() {
+        let (hidden, regular): (Vec<&PokemonAbility>, Vec<_>) =
+            abilities.iter().partition(|ability| ability.is_hidden);
+        let record = vec![
+            (
+                "abilities".to_owned(),
+                ints(regular.iter().map(|ability| ability.ability_id)),
+            ),
+            (
+                "hidden_ability".to_owned(),
+                int(hidden.first().map_or(0, |ability| ability.ability_id)),
+            ),
+        ];
+        table.insert(id, Value::Record(record));
+    }
+    table
+}
+
+/// Slot-1 and slot-2 type ids, with 0 for single-type Pokemon.
+pub fn pokemon_types(dex: &Pokedex) -> Table {
+    let mut table = table("pokemon_types", dex, &[Dataset::PokemonTypes]);
+    for (id, types) in dex.pokemon_types() {
+        table.insert(
+            id,
+            ints([types.first.id(), types.second.map_or(EMPTY_TYPE, Type::id)]),
+        );
+    }
+    table
+}
+
+pub fn pokemon_weaknesses(dex: &Pokedex) -> Table {
+    let type_chart = dex.type_chart();
+
+    let mut table = table(
+        "pokemon_weaknesses",
+        dex,
+        &[Dataset::PokemonTypes, Dataset::TypeEfficacy],
+    );
+    for (id, types) in dex.pokemon_types() {
+        table.insert(
+            id,
+            ints(type_chart.weaknesses(types).into_iter().map(Type::id)),
+        );
+    }
+    table
+}
+
+/// Attacking type ids per multiplier bucket, from `quadruple` down to `immune`.
+pub fn pokemon_defenses(dex: &Pokedex) -> Table {
+    let type_chart = dex.type_chart();
+
+    let mut table = table(
+        "pokemon_defenses",
+        dex,
+        &[Dataset::PokemonTypes, Dataset::TypeEfficacy],
+    );
+    for (id, types) in dex.pokemon_types() {
+        let profile = type_chart.defensive_profile(types);
+        let buckets = profile
+            .buckets()
+            .into_iter()
+            .map(|(name, types)| (name.to_owned(), ints(types.iter().map(|type_| type_.id()))))
+            .collect();
+        table.insert(id, Value::Record(buckets));
+    }
+    table
+}
+
+pub fn type_efficacy(dex: &Pokedex) -> Table {
+    let mut table = table("type_efficacy", dex, &[Dataset::TypeEfficacy]);
+    for (damage_id, targets) in dex.type_chart().to_efficacy() {
+        let targets = targets
+            .into_iter()
+            .map(|(target_id, factor)| (target_id, int(factor)))
+            .collect();
+        table.insert(damage_id, Value::Map(targets));
+    }
+    table
+}
+
+/// Ids of the moves matching `filter` grouped by type, usually [`MoveFilter::damaging`].
+pub fn moves(dex: &Pokedex, filter: &MoveFilter) -> Table {
+    let mut table = table("moves", dex, &[Dataset::Moves]);
+    dex.query_moves(filter)
+        .into_group_map_by(|move_| move_.type_id)
+        .into_iter()
+        .for_each(|(type_id, moves)| {
+            table.insert(type_id, ints(moves.iter().map(|move_| move_.id)));
+        });
+    table
+}
+
+/// The moves each Pokemon learns in a version group, level-up moves first by
+/// level, restricted to `methods` unless empty.
+pub fn learnsets(dex: &Pokedex, version_group_id: u64, methods: &[LearnMethod]) -> Table {
+    let mut table = table("learnsets", dex, &[Dataset::PokemonMoves]);
+    for (pokemon_id, learnset) in dex.learnsets(version_group_id) {
+        let moves: Vec<Value> = learnset
+            .iter()
+            .filter(|learnable| methods.is_empty() || methods.contains(&learnable.method))
+            .map(|learnable| {
+                Value::Record(vec![
+                    ("move_id".to_owned(), int(learnable.move_id)),
+                    (
+                        "method".to_owned(),
+                        Value::Str(learnable.method.identifier().to_owned()),
+                    ),
+                    ("level".to_owned(), int(learnable.level)),
+                ])
+            })
+            .collect();
+        if !moves.is_empty() {
+            table.insert(pokemon_id, Value::List(moves));
+        }
+    }
+    table
+}
+
+/// Italian move names.
+pub fn move_names(dex: &Pokedex) -> Table {
+    let mut table = table("move_names", dex, &[Dataset::MoveNames]);
+    for (id, name) in dex.move_names(Language::It.id()) {
+        table.insert(id, Value::Str(name.to_owned()));
+    }
+    table
+}
+
+/// Move names in the first language of `languages` having a translation,
+/// falling back to the identifier from `moves.csv` when it is loaded.
+pub fn localized_move_names(dex: &Pokedex, languages: &[Language]) -> Table {
+    let mut table = table(
+        &localized_name("move_names", languages),
+        dex,
+        &[Dataset::Moves, Dataset::MoveNames],
+    );
+    let ids: Vec<u64> = dex
+        .moves()
+        .map(|move_| move_.id)
+        .merge(dex.named_moves())
+        .dedup()
+        .collect();
+    for id in ids {
+        let name = dex
+            .localized_move_name(id, languages)
+            .or_else(|| dex.move_by_id(id).map(|move_| move_.identifier.as_str()));
+        if let Some(name) = name {
+            table.insert(id, Value::Str(name.to_owned()));
+        }
+    }
+    table
+}
@@ -0,0 +1,289 @@
+//! Defensive report of a party against every attacking type.
+
+use crate::emit::escape_json;
+use crate::pokedex::Types;
+use crate::type_chart::TypeChart;
+use crate::types::{Effectiveness, Type};
+use std::fmt::Write;
+
+/// Largest party the games allow.
+pub const MAX_TEAM_SIZE: usize = 6;
+
+/// Members weak to a type from which it counts as a shared weakness.
+const SHARED_WEAKNESS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub pokemon_id: u64,
+    pub identifier: String,
+    pub types: Types,
+}
+
+/// How one attacking type fares against every member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRow {
+    pub attacking: Type,
+    /// One per member, `None` when the chart lacks an entry.
+    pub multipliers: Vec<Option<Effectiveness>>,
+    pub weak: usize,
+    /// Resisting members, not counting the immune ones.
+    pub resist: usize,
+    pub immune: usize,
+}
+
+impl TypeRow {
+    pub fn is_shared_weakness(&self) -> bool {
+        self.weak >= SHARED_WEAKNESS
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamReport {
+    pub members: Vec<Member>,
+    /// One row per type of the chart, in id order.
+    pub rows: Vec<TypeRow>,
+}
+
+impl TeamReport {
+    pub fn new(chart: &TypeChart, members: Vec<Member>) -> TeamReport {
+        let rows = chart
+            .types()
+            .iter()
+            .map(|attacking| {
+                let multipliers: Vec<_> = members
+                    .iter()
+                    .map(|member| chart.against(*attacking, member.types))
+                    .collect();
+                let count = |f: fn(Effectiveness) -> bool| {
+                    multipliers
+                        .iter()
+                        .filter(|multiplier| multiplier.is_some_and(f))
+                        .count()
+                };
+                TypeRow {
+                    attacking: *attacking,
+                    weak: count(Effectiveness::is_super_effective),
+                    resist: count(Effectiveness::is_resisted),
+                    immune: count(Effectiveness::is_immune),
+                    multipliers,
+                }
+            })
+            .collect();
+
+        TeamReport { members, rows }
+    }
+
+    pub fn shared_weaknesses(&self) -> impl Iterator<Item = Type> + '_ {
+        self.rows
+            .iter()
+            .filter(|row| row.is_shared_weakness())
+            .map(|row| row.attacking)
+    }
+
+    /// Aligned columns for a terminal, shared weaknesses marked with `!`.
+    pub fn to_table(&self) -> String {
+        const COUNTS: [&str; 3] = ["Weak", "Resist", "Immune"];
+        let widths: Vec<usize> = self
+            .members
+            .iter()
+            .map(|member| member.identifier.chars().count().max(5))
+            .collect();
+
+        let mut out = format!("{:<10}", "Attacking");
+        for (member, width) in self.members.iter().zip(&widths) {
+            let _ = write!(out, "  {:>width$}", member.identifier);
+        }
+        for name in COUNTS {
+            let _ = write!(out, "  {name:>6}");
+        }
+        out.push('\n');
+
+        for row in &self.rows {
+            let _ = write!(out, "{:<10}", row.attacking);
+            for (multiplier, width) in row.multipliers.iter().zip(&widths) {
+                let multiplier = multiplier.map_or("-".to_owned(), |m| m.to_string());
+                let _ = write!(out, "  {multiplier:>width$}");
+            }
+            for count in [row.weak, row.resist, row.immune] {
+                let _ = write!(out, "  {count:>6}");
+            }
+            if row.is_shared_weakness() {
+                out.push_str("  !");
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// One line per attacking type, multipliers as decimals such as `0.25`.
+    pub fn to_csv(&self) -> String {
+        let mut header = vec!["attacking_type".to_owned()];
+        header.extend(self.members.iter().map(|member| member.identifier.clone()));
+        header.extend(["weak", "resist", "immune", "shared_weakness"].map(String::from));
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        // Writing to memory cannot fail
+        writer.write_record(&header).unwrap();
+        for row in &self.rows {
+            let mut record = vec![row.attacking.identifier().to_owned()];
+            record.extend(
+                row.multipliers
+                    .iter()
+                    .map(|multiplier| multiplier.map_or(String::new(), decimal)),
+            );
+            record.extend([row.weak, row.resist, row.immune].map(|count| count.to_string()));
+            record.push(row.is_shared_weakness().to_string());
+            writer.write_record(&record).unwrap();
+        }
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"members\": [");
+        for (index, member) in self.members.iter().enumerate() {
+            let types: Vec<String> = member.types.iter().map(|t| t.id().to_string()).collect();
+            let _ = write!(
+                out,
+                "{}\n    {{ \"id\": {}, \"identifier\": {}, \"types\": [{}] }}",
+                if index > 0 { "," } else { "" },
+                member.pokemon_id,
+                escape_json(&member.identifier),
+                types.join(", ")
+            );
+        }
+        out.push_str("\n  ],\n  \"types\": [");
+        for (index, row) in self.rows.iter().enumerate() {
+            let multipliers: Vec<String> = row
+                .multipliers
+                .iter()
+                .map(|multiplier| multiplier.map_or("null".to_owned(), decimal))
+                .collect();
+            let _ = write!(
+                out,
+                "{}\n    {{ \"type\": {}, \"multipliers\": [{}], \"weak\": {}, \"resist\": {}, \
+                 \"immune\": {}, \"shared_weakness\": {} }}",
+                if index > 0 { "," } else { "" },
+                escape_json(row.attacking.identifier()),
+                multipliers.join(", "),
+                row.weak,
+                row.resist,
+                row.immune,
+                row.is_shared_weakness()
+            );
+        }
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+}
+
+fn decimal(effectiveness: Effectiveness) -> String {
+    effectiveness.multiplier().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(pokemon_id: u64, identifier: &str, first: Type, second: Option<Type>) -> Member {
+        Member {
+            pokemon_id,
+            identifier: identifier.to_owned(),
+            types: Types { first, second },
+        }
+    }
+
+    fn team() -> TeamReport {
+        TeamReport::new(
+            TypeChart::standard(),
+            vec![
+                member(6, "charizard", Type::Fire, Some(Type::Flying)),
+                member(130, "gyarados", Type::Water, Some(Type::Flying)),
+                member(94, "gengar", Type::Ghost, Some(Type::Poison)),
+            ],
+        )
+    }
+
+    fn row(report: &TeamReport, attacking: Type) -> &TypeRow {
+        &report.rows[attacking.index()]
+    }
+
+    #[test]
+    fn test_counts() {
+        let report = team();
+
+        assert_eq!(report.rows.len(), 18);
+        let electric = row(&report, Type::Electric);
+        assert_eq!(
+            electric.multipliers,
+            [
+                Some(Effectiveness::DOUBLE),
+                Some(Effectiveness::QUADRUPLE),
+                Some(Effectiveness::NEUTRAL)
+            ]
+        );
+        assert_eq!((electric.weak, electric.resist, electric.immune), (2, 0, 0));
+
+        let ground = row(&report, Type::Ground);
+        assert_eq!((ground.weak, ground.resist, ground.immune), (1, 0, 2));
+
+        let bug = row(&report, Type::Bug);
+        assert_eq!((bug.weak, bug.resist, bug.immune), (0, 3, 0));
+    }
+
+    #[test]
+    fn test_shared_weaknesses() {
+        let report = team();
+
+        assert_eq!(
+            report.shared_weaknesses().collect::<Vec<_>>(),
+            [Type::Rock, Type::Electric]
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let table = team().to_table();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 19);
+        assert_eq!(
+            lines[0],
+            "Attacking   charizard  gyarados  gengar    Weak  Resist  Immune"
+        );
+        assert_eq!(
+            lines[Type::Electric.id() as usize],
+            "Electric           2x        4x      1x       2       0       0  !"
+        );
+        assert_eq!(
+            lines[Type::Grass.id() as usize],
+            "Grass            1/4x        1x    1/2x       0       2       0"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = team().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "attacking_type,charizard,gyarados,gengar,weak,resist,immune,shared_weakness"
+        );
+        assert_eq!(lines[5], "ground,0,0,2,1,0,2,false");
+        assert_eq!(lines[12], "grass,0.25,1,0.5,0,2,0,false");
+    }
+
+    #[test]
+    fn test_json() {
+        let json = team().to_json();
+
+        assert!(json.starts_with(
+            "{\n  \"members\": [\n    { \"id\": 6, \"identifier\": \"charizard\", \"types\": [10, 3] },"
+        ));
+        assert!(json.contains(
+            "{ \"type\": \"rock\", \"multipliers\": [4, 2, 1], \"weak\": 2, \"resist\": 0, \
+             \"immune\": 0, \"shared_weakness\": true }"
+        ));
+        assert!(json.ends_with("}\n  ]\n}\n"));
+    }
+}
@@ -0,0 +1,35 @@
+//! Helpers for the tests of the binaries. The library is not built with
+//! `cfg(test)` when they are tested, so each includes this file with
+//! `#[path]` instead of importing it.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temporary directory, unique to the
+/// test and the process, removed on drop.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        let path = env::temp_dir().join(format!(
+            "{}-{name}-{}",
+            env!("CARGO_CRATE_NAME"),
+            std::process::id()
+        ));
+        // Left over by a process that had the same id
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::csv_source::{CsvSource, Row};
+use crate::error::{Diagnostics, Error, Result};
+use crate::get_type_efficacy;
+use crate::pokedex::{TypeEfficacy, Types};
+use crate::types::{Effectiveness, Type};
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+
+const TYPE_COUNT: usize = Type::ALL.len();
+
+lazy_static! {
+    static ref STANDARD: TypeChart = {
+        let mut chart = TypeChart::empty();
+        for (damage, targets) in get_type_efficacy() {
+            for (target, factor) in targets {
+                // The literal only holds known types and factors
+                chart.set(
+                    Type::from_id(damage).unwrap(),
+                    Type::from_id(target).unwrap(),
+                    Effectiveness::from_percent(factor).unwrap(),
+                );
+            }
+        }
+        chart
+    };
+}
+
+/// Effectiveness between every pair of types, stored as a dense matrix.
+///
+/// Pairs can be missing, so older generations' charts without Dark, Steel
+/// or Fairy load as well as the current one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeChart {
+    /// Types having at least one entry, in id order.
+    types: Vec<Type>,
+    /// Indexed by attacking then defending [`Type::index`].
+    factors: [[Option<Effectiveness>; TYPE_COUNT]; TYPE_COUNT],
+}
+
+impl TypeChart {
+    /// The current 18-type chart, built on first use.
+    pub fn standard() -> &'static TypeChart {
+        &STANDARD
+    }
+
+    pub fn empty() -> TypeChart {
+        TypeChart {
+            types: Vec::new(),
+            factors: [[None; TYPE_COUNT]; TYPE_COUNT],
+        }
+    }
+
+    pub fn set(&mut self, attacking: Type, defending: Type, effectiveness: Effectiveness) {
+        self.factors[attacking.index()][defending.index()] = Some(effectiveness);
+        for type_ in [attacking, defending] {
+            if let Err(position) = self.types.binary_search(&type_) {
+                self.types.insert(position, type_);
+            }
+        }
+    }
+
+    pub fn from_csv(input: &CsvSource, diagnostics: &mut Diagnostics) -> Result<TypeChart> {
+        let mut chart = TypeChart::empty();
+        for Row { at, value } in input.rows::<TypeEfficacy>(diagnostics)? {
+            let (Some(damage), Some(target)) =
+                (Type::from_id(value.damage), Type::from_id(value.target))
+            else {
+                let type_id = if Type::from_id(value.damage).is_none() {
+                    value.damage
+                } else {
+                    value.target
+                };
+                diagnostics.report(Error::UnknownType { at, type_id })?;
+                continue;
+            };
+            let Some(effectiveness) = Effectiveness::from_percent(value.factor) else {
+                diagnostics.report(Error::MalformedRow {
+                    at,
+                    column: Some("damage_factor".to_owned()),
+                    message: format!("{} is not 0 or a power of two percent", value.factor),
+                })?;
+                continue;
+            };
+            chart.set(damage, target, effectiveness);
+        }
+        Ok(chart)
+    }
+
+    /// Types having at least one entry, in id order.
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+
+    pub fn contains(&self, type_: Type) -> bool {
+        self.types.binary_search(&type_).is_ok()
+    }
+
+    /// Effectiveness of one type against another, if the chart has the pair.
+    pub fn factor(&self, attacking: Type, defending: Type) -> Option<Effectiveness> {
+        self.factors[attacking.index()][defending.index()]
+    }
+
+    /// Every single type of the chart followed by every pair, in id order.
+    pub fn combinations(&self) -> Vec<Types> {
+        let singles = self.types.iter().map(|first| Types {
+            first: *first,
+            second: None,
+        });
+        let pairs = self.types.iter().enumerate().flat_map(|(i, first)| {
+            self.types[i + 1..].iter().map(|second| Types {
+                first: *first,
+                second: Some(*second),
+            })
+        });
+        singles.chain(pairs).collect()
+    }
+
+    /// Combined effectiveness against a Pokemon of `types`.
+    pub fn against(&self, attacking: Type, types: Types) -> Option<Effectiveness> {
+        types
+            .iter()
+            .try_fold(Effectiveness::NEUTRAL, |acc, defending| {
+                Some(acc * self.factor(attacking, defending)?)
+            })
+    }
+
+    /// Attacking types that hit a Pokemon of `types` for more than neutral damage.
+    pub fn weaknesses(&self, types: Types) -> Vec<Type> {
+        self.types
+            .iter()
+            .copied()
+            .filter(|attacking| {
+                self.against(*attacking, types)
+                    .is_some_and(Effectiveness::is_super_effective)
+            })
+            .collect()
+    }
+
+    /// Every attacking type of the chart sorted by how well it hits a Pokemon of `types`.
+    ///
+    /// Attacking types missing an entry against either defending type are left out.
+    pub fn defensive_profile(&self, types: Types) -> DefensiveProfile {
+        let mut profile = DefensiveProfile::default();
+        for attacking in &self.types {
+            let Some(effectiveness) = self.against(*attacking, types) else {
+                continue;
+            };
+            let bucket = match effectiveness {
+                _ if effectiveness.is_immune() => &mut profile.immune,
+                _ if effectiveness >= Effectiveness::QUADRUPLE => &mut profile.quadruple,
+                Effectiveness::DOUBLE => &mut profile.double,
+                Effectiveness::NEUTRAL => &mut profile.neutral,
+                Effectiveness::HALF => &mut profile.half,
+                _ => &mut profile.quarter,
+            };
+            bucket.push(*attacking);
+        }
+        profile
+    }
+
+    /// Factors in percent keyed by attacking then defending type id, like `type_efficacy.csv`.
+    pub fn to_efficacy(&self) -> BTreeMap<u64, BTreeMap<u64, u64>> {
+        let mut efficacy: BTreeMap<u64, BTreeMap<u64, u64>> = BTreeMap::new();
+        for attacking in &self.types {
+            for defending in &self.types {
+                if let Some(factor) = self.factor(*attacking, *defending) {
+                    efficacy
+                        .entry(attacking.id())
+                        .or_default()
+                        .insert(defending.id(), factor.percent());
+                }
+            }
+        }
+        efficacy
+    }
+}
+
+/// Attacking types grouped by their multiplier against one Pokemon, each in id order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefensiveProfile {
+    /// 4x, or more with custom charts.
+    pub quadruple: Vec<Type>,
+    pub double: Vec<Type>,
+    pub neutral: Vec<Type>,
+    pub half: Vec<Type>,
+    /// 1/4x, or less with custom charts.
+    pub quarter: Vec<Type>,
+    pub immune: Vec<Type>,
+}
+
+impl DefensiveProfile {
+    /// Bucket names paired with their types, from most to least damage taken.
+    pub fn buckets(&self) -> [(&'static str, &[Type]); 6] {
+        [
+            ("quadruple", &self.quadruple),
+            ("double", &self.double),
+            ("neutral", &self.neutral),
+            ("half", &self.half),
+            ("quarter", &self.quarter),
+            ("immune", &self.immune),
+        ]
+    }
+
+    /// Quadruple and double weaknesses, in id order.
+    pub fn weaknesses(&self) -> Vec<Type> {
+        let mut weaknesses = [self.quadruple.as_slice(), &self.double].concat();
+        weaknesses.sort();
+        weaknesses
+    }
+}
+
+impl Default for TypeChart {
+    fn default() -> Self {
+        TypeChart::standard().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OnBadRow;
+
+    const TYPE_EFFICACY: &str = include_str!("../csv/type_efficacy.csv");
+
+    fn csv_chart(input: &str) -> TypeChart {
+        TypeChart::from_csv(
+            &CsvSource::new("type_efficacy.csv", input),
+            &mut Diagnostics::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_csv_matches_literal() {
+        let chart = csv_chart(TYPE_EFFICACY);
+
+        assert_eq!(chart.types(), Type::ALL);
+        assert_eq!(chart.to_efficacy(), get_type_efficacy());
+        assert_eq!(&chart, TypeChart::standard());
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let input = "damage_type_id,target_type_id,damage_factor\n1,1,100\n1,19,100\n2,1,150\n";
+        let source = CsvSource::new("type_efficacy.csv", input);
+
+        let err = TypeChart::from_csv(&source, &mut Diagnostics::default()).unwrap_err();
+        assert!(matches!(err, Error::UnknownType { type_id: 19, .. }));
+
+        let mut diagnostics = Diagnostics::new(OnBadRow::SkipAndWarn);
+        let chart = TypeChart::from_csv(&source, &mut diagnostics).unwrap();
+        assert_eq!(chart.types(), [Type::Normal]);
+        assert!(matches!(
+            &diagnostics.warnings()[1],
+            Error::MalformedRow { column: Some(column), .. } if column == "damage_factor"
+        ));
+    }
+
+    #[test]
+    fn test_factor() {
+        let chart = TypeChart::standard();
+
+        assert_eq!(
+            chart.factor(Type::Fire, Type::Grass),
+            Some(Effectiveness::DOUBLE)
+        );
+        assert_eq!(
+            chart.factor(Type::Normal, Type::Ghost),
+            Some(Effectiveness::IMMUNE)
+        );
+        assert_eq!(
+            chart.factor(Type::Water, Type::Fire),
+            Some(Effectiveness::DOUBLE)
+        );
+        assert_eq!(
+            chart.factor(Type::Fire, Type::Water),
+            Some(Effectiveness::HALF)
+        );
+    }
+
+    #[test]
+    fn test_against() {
+        let chart = TypeChart::standard();
+        let charizard = Types {
+            first: Type::Fire,
+            second: Some(Type::Flying),
+        };
+
+        assert_eq!(
+            chart.against(Type::Rock, charizard),
+            Some(Effectiveness::QUADRUPLE)
+        );
+        assert_eq!(
+            chart.against(Type::Ground, charizard),
+            Some(Effectiveness::IMMUNE)
+        );
+        assert_eq!(
+            chart.against(Type::Grass, charizard),
+            Some(Effectiveness::QUARTER)
+        );
+    }
+
+    #[test]
+    fn test_weaknesses() {
+        let chart = TypeChart::standard();
+        let bulbasaur = Types {
+            first: Type::Grass,
+            second: Some(Type::Poison),
+        };
+        let gengar = Types {
+            first: Type::Ghost,
+            second: Some(Type::Poison),
+        };
+        // Ground is immune to Electric, which must not count as a weakness
+        let gliscor = Types {
+            first: Type::Ground,
+            second: Some(Type::Flying),
+        };
+
+        assert_eq!(
+            chart.weaknesses(bulbasaur),
+            [Type::Flying, Type::Fire, Type::Psychic, Type::Ice]
+        );
+        assert_eq!(
+            chart.weaknesses(gengar),
+            [Type::Ground, Type::Ghost, Type::Psychic, Type::Dark]
+        );
+        assert_eq!(chart.weaknesses(gliscor), [Type::Water, Type::Ice]);
+    }
+
+    #[test]
+    fn test_defensive_profile() {
+        let chart = TypeChart::standard();
+        let charizard = Types {
+            first: Type::Fire,
+            second: Some(Type::Flying),
+        };
+
+        let profile = chart.defensive_profile(charizard);
+
+        assert_eq!(profile.quadruple, [Type::Rock]);
+        assert_eq!(profile.double, [Type::Water, Type::Electric]);
+        assert_eq!(
+            profile.half,
+            [Type::Fighting, Type::Steel, Type::Fire, Type::Fairy]
+        );
+        assert_eq!(profile.quarter, [Type::Bug, Type::Grass]);
+        assert_eq!(profile.immune, [Type::Ground]);
+        assert_eq!(profile.neutral.len(), 18 - 10);
+        assert_eq!(profile.weaknesses(), chart.weaknesses(charizard));
+    }
+
+    #[test]
+    fn test_defensive_profile_single_type() {
+        let chart = TypeChart::standard();
+        let gengar = Types {
+            first: Type::Ghost,
+            second: Some(Type::Poison),
+        };
+        let sableye = Types {
+            first: Type::Dark,
+            second: Some(Type::Ghost),
+        };
+        let normal = Types {
+            first: Type::Normal,
+            second: None,
+        };
+
+        assert_eq!(
+            chart.defensive_profile(gengar).immune,
+            [Type::Normal, Type::Fighting]
+        );
+        assert_eq!(chart.defensive_profile(sableye).weaknesses(), [Type::Fairy]);
+
+        let profile = chart.defensive_profile(normal);
+        assert_eq!(profile.double, [Type::Fighting]);
+        assert_eq!(profile.immune, [Type::Ghost]);
+        assert_eq!(profile.neutral.len(), 16);
+        assert!(profile.half.is_empty() && profile.quarter.is_empty());
+    }
+
+    #[test]
+    fn test_combinations() {
+        let chart = TypeChart::standard();
+        let combinations = chart.combinations();
+
+        assert_eq!(combinations.len(), 18 + 18 * 17 / 2);
+        assert_eq!(
+            combinations[0],
+            Types {
+                first: Type::Normal,
+                second: None
+            }
+        );
+        assert_eq!(
+            combinations[18],
+            Types {
+                first: Type::Normal,
+                second: Some(Type::Fighting)
+            }
+        );
+        assert!(!combinations.contains(&Types {
+            first: Type::Fighting,
+            second: Some(Type::Normal)
+        }));
+        assert!(combinations.contains(&Types {
+            first: Type::Flying,
+            second: Some(Type::Water)
+        }));
+    }
+
+    #[test]
+    fn test_partial_chart() {
+        // A Gen 1 style chart: no Dark, Steel or Fairy
+        let input: String = TYPE_EFFICACY
+            .lines()
+            .filter(|line| {
+                line.split(',')
+                    .take(2)
+                    .all(|id| !["9", "17", "18"].contains(&id))
+            })
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        let chart = csv_chart(&input);
+
+        assert_eq!(chart.types().len(), 15);
+        assert!(!chart.contains(Type::Fairy));
+        assert_eq!(chart.factor(Type::Dark, Type::Psychic), None);
+        assert_eq!(
+            chart.factor(Type::Psychic, Type::Fighting),
+            Some(Effectiveness::DOUBLE)
+        );
+        let clefairy = Types {
+            first: Type::Normal,
+            second: None,
+        };
+        assert_eq!(chart.weaknesses(clefairy), [Type::Fighting]);
+    }
+}
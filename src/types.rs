@@ -0,0 +1,275 @@
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+
+/// The 18 Pokemon types, with their PokeAPI ids as discriminants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Type {
+    Normal = 1,
+    Fighting,
+    Flying,
+    Poison,
+    Ground,
+    Rock,
+    Bug,
+    Ghost,
+    Steel,
+    Fire,
+    Water,
+    Grass,
+    Electric,
+    Psychic,
+    Ice,
+    Dragon,
+    Dark,
+    Fairy,
+}
+
+impl Type {
+    pub const ALL: [Type; 18] = [
+        Type::Normal,
+        Type::Fighting,
+        Type::Flying,
+        Type::Poison,
+        Type::Ground,
+        Type::Rock,
+        Type::Bug,
+        Type::Ghost,
+        Type::Steel,
+        Type::Fire,
+        Type::Water,
+        Type::Grass,
+        Type::Electric,
+        Type::Psychic,
+        Type::Ice,
+        Type::Dragon,
+        Type::Dark,
+        Type::Fairy,
+    ];
+
+    pub fn id(self) -> u64 {
+        self as u64
+    }
+
+    pub fn from_id(id: u64) -> Option<Type> {
+        Type::ALL.into_iter().find(|type_| type_.id() == id)
+    }
+
+    /// 0-based position in [`Type::ALL`].
+    pub fn index(self) -> usize {
+        self as usize - 1
+    }
+
+    /// Lowercase PokeAPI identifier, e.g. `fighting`.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            Type::Normal => "normal",
+            Type::Fighting => "fighting",
+            Type::Flying => "flying",
+            Type::Poison => "poison",
+            Type::Ground => "ground",
+            Type::Rock => "rock",
+            Type::Bug => "bug",
+            Type::Ghost => "ghost",
+            Type::Steel => "steel",
+            Type::Fire => "fire",
+            Type::Water => "water",
+            Type::Grass => "grass",
+            Type::Electric => "electric",
+            Type::Psychic => "psychic",
+            Type::Ice => "ice",
+            Type::Dragon => "dragon",
+            Type::Dark => "dark",
+            Type::Fairy => "fairy",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Type> {
+        Type::ALL
+            .into_iter()
+            .find(|type_| type_.identifier().eq_ignore_ascii_case(identifier))
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let identifier = self.identifier();
+        f.pad(&format!(
+            "{}{}",
+            identifier[0..1].to_uppercase(),
+            &identifier[1..]
+        ))
+    }
+}
+
+/// Parses an identifier such as `fire` or a PokeAPI id such as `10`.
+impl FromStr for Type {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u64>()
+            .ok()
+            .and_then(Type::from_id)
+            .or_else(|| Type::from_identifier(s))
+            .ok_or_else(|| format!("unknown type `{s}`"))
+    }
+}
+
+/// A damage multiplier: zero or a power of two.
+///
+/// Multipliers compose exactly, so dual-type results such as 1/4 or 4 never
+/// go through rounded percentages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Effectiveness(Option<i8>);
+
+impl Effectiveness {
+    pub const IMMUNE: Effectiveness = Effectiveness(None);
+    pub const QUARTER: Effectiveness = Effectiveness(Some(-2));
+    pub const HALF: Effectiveness = Effectiveness(Some(-1));
+    pub const NEUTRAL: Effectiveness = Effectiveness(Some(0));
+    pub const DOUBLE: Effectiveness = Effectiveness(Some(1));
+    pub const QUADRUPLE: Effectiveness = Effectiveness(Some(2));
+
+    /// Converts a `damage_factor` such as 50 or 200; `None` if it is not 0 or a power of two.
+    pub fn from_percent(percent: u64) -> Option<Effectiveness> {
+        match percent {
+            0 => Some(Effectiveness::IMMUNE),
+            _ if percent.is_multiple_of(100) && (percent / 100).is_power_of_two() => {
+                Some(Effectiveness(Some((percent / 100).trailing_zeros() as i8)))
+            }
+            _ if 100u64.is_multiple_of(percent) && (100 / percent).is_power_of_two() => Some(
+                Effectiveness(Some(-((100 / percent).trailing_zeros() as i8))),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The multiplier in percent, rounded down below 1/4.
+    pub fn percent(self) -> u64 {
+        match self.0 {
+            None => 0,
+            Some(exponent) if exponent >= 0 => 100 << exponent,
+            Some(exponent) => 100 >> -exponent,
+        }
+    }
+
+    pub fn multiplier(self) -> f64 {
+        self.0.map_or(0.0, |exponent| 2f64.powi(exponent.into()))
+    }
+
+    pub fn is_immune(self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn is_super_effective(self) -> bool {
+        self > Effectiveness::NEUTRAL
+    }
+
+    /// Resisted, but not immune.
+    pub fn is_resisted(self) -> bool {
+        !self.is_immune() && self < Effectiveness::NEUTRAL
+    }
+}
+
+impl Default for Effectiveness {
+    fn default() -> Self {
+        Effectiveness::NEUTRAL
+    }
+}
+
+impl Mul for Effectiveness {
+    type Output = Effectiveness;
+
+    fn mul(self, rhs: Effectiveness) -> Effectiveness {
+        match (self.0, rhs.0) {
+            // Multiplying powers of two adds their exponents
+            (Some(a), Some(b)) => Effectiveness(Some(a.saturating_add(b))),
+            _ => Effectiveness::IMMUNE,
+        }
+    }
+}
+
+impl fmt::Display for Effectiveness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let multiplier = match self.0 {
+            None => "0x".to_owned(),
+            Some(exponent) if exponent >= 0 => format!("{}x", 1u64 << exponent),
+            Some(exponent) => format!("1/{}x", 1u64 << -exponent),
+        };
+        f.pad(&multiplier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_conversions() {
+        for (index, type_) in Type::ALL.into_iter().enumerate() {
+            assert_eq!(type_.index(), index);
+            assert_eq!(Type::from_id(type_.id()), Some(type_));
+            assert_eq!(type_.identifier().parse(), Ok(type_));
+        }
+        assert_eq!(Type::Fire.id(), 10);
+        assert_eq!(Type::from_id(0), None);
+        assert_eq!(Type::from_id(19), None);
+        assert_eq!("18".parse(), Ok(Type::Fairy));
+        assert_eq!("Electric".parse(), Ok(Type::Electric));
+        assert!("shadow".parse::<Type>().is_err());
+        assert_eq!(Type::Psychic.to_string(), "Psychic");
+    }
+
+    #[test]
+    fn test_effectiveness_percent() {
+        for percent in [0, 25, 50, 100, 200, 400] {
+            assert_eq!(
+                Effectiveness::from_percent(percent).unwrap().percent(),
+                percent
+            );
+        }
+        assert_eq!(Effectiveness::from_percent(150), None);
+        assert_eq!(Effectiveness::from_percent(300), None);
+        assert_eq!(
+            Effectiveness::from_percent(200),
+            Some(Effectiveness::DOUBLE)
+        );
+    }
+
+    #[test]
+    fn test_effectiveness_composes() {
+        assert_eq!(
+            Effectiveness::DOUBLE * Effectiveness::DOUBLE,
+            Effectiveness::QUADRUPLE
+        );
+        assert_eq!(
+            Effectiveness::HALF * Effectiveness::HALF,
+            Effectiveness::QUARTER
+        );
+        assert_eq!(
+            Effectiveness::DOUBLE * Effectiveness::HALF,
+            Effectiveness::NEUTRAL
+        );
+        assert_eq!(
+            Effectiveness::QUADRUPLE * Effectiveness::IMMUNE,
+            Effectiveness::IMMUNE
+        );
+        assert_eq!(
+            (Effectiveness::HALF * Effectiveness::QUARTER).multiplier(),
+            0.125
+        );
+    }
+
+    #[test]
+    fn test_effectiveness_order() {
+        assert!(Effectiveness::IMMUNE < Effectiveness::QUARTER);
+        assert!(Effectiveness::QUARTER < Effectiveness::HALF);
+        assert!(Effectiveness::QUADRUPLE.is_super_effective());
+        assert!(!Effectiveness::IMMUNE.is_super_effective());
+        assert!(!Effectiveness::IMMUNE.is_resisted());
+        assert!(Effectiveness::QUARTER.is_resisted());
+        assert_eq!(Effectiveness::QUARTER.to_string(), "1/4x");
+        assert_eq!(Effectiveness::QUADRUPLE.to_string(), "4x");
+        assert_eq!(Effectiveness::IMMUNE.to_string(), "0x");
+    }
+}